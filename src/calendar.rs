@@ -0,0 +1,348 @@
+//! Working time.
+//!
+//! Durations, work and lags are counted in working time: a task lasting 16 h
+//! that starts on Friday afternoon, with the standard calendar, only finishes
+//! on Tuesday. The calendar of the project tells which days of the week are
+//! worked, the hours worked on each of them, and the holidays.
+//!
+//! Working hours are wall-clock times in the time zone of the project, in
+//! which dates are also typed and shown. Days where clocks change have one
+//! hour less or more, which working time accounts for.
+
+use std::collections::BTreeSet;
+
+use chrono::{
+    DateTime, Datelike, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta,
+    TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+/// Days of the week, from Monday.
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calendar {
+    /// Whether each day of the week is worked, from Monday to Sunday.
+    working_days: [bool; 7],
+    /// Periods worked on working days, sorted and not overlapping. A period
+    /// ending at midnight runs to the end of the day.
+    working_hours: Vec<(NaiveTime, NaiveTime)>,
+    holidays: BTreeSet<NaiveDate>,
+    time_zone: Tz,
+}
+
+impl Default for Calendar {
+    /// Monday to Friday, from 8:00 to 12:00 and from 13:00 to 17:00.
+    fn default() -> Self {
+        let time = |hour| {
+            NaiveTime::from_hms_opt(hour, 0, 0)
+                .expect("Hardcoded times should be valid. This is a bug.")
+        };
+
+        Calendar {
+            working_days: [true, true, true, true, true, false, false],
+            working_hours: vec![(time(8), time(12)), (time(13), time(17))],
+            holidays: BTreeSet::new(),
+            time_zone: Tz::UTC,
+        }
+    }
+}
+
+impl Calendar {
+    /// Every hour of every day, so that working time is elapsed time.
+    #[cfg(test)]
+    pub fn continuous() -> Self {
+        Calendar {
+            working_days: [true; 7],
+            working_hours: vec![(NaiveTime::MIN, NaiveTime::MIN)],
+            holidays: BTreeSet::new(),
+            time_zone: Tz::UTC,
+        }
+    }
+
+    pub fn works_on(&self, weekday: Weekday) -> bool {
+        self.working_days[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Fails if no other day of the week would be worked.
+    pub fn set_works_on(&mut self, weekday: Weekday, works: bool) -> Result<(), ()> {
+        let mut working_days = self.working_days;
+        working_days[weekday.num_days_from_monday() as usize] = works;
+        if !working_days.contains(&true) {
+            return Err(());
+        }
+        self.working_days = working_days;
+        Ok(())
+    }
+
+    pub fn working_hours(&self) -> &[(NaiveTime, NaiveTime)] {
+        &self.working_hours
+    }
+
+    /// Fails if there are no periods, or if some end before they start or
+    /// overlap.
+    pub fn set_working_hours(
+        &mut self,
+        mut working_hours: Vec<(NaiveTime, NaiveTime)>,
+    ) -> Result<(), ()> {
+        working_hours.sort();
+        let ends = |&(from, to): &(NaiveTime, NaiveTime)| {
+            (from, if to == NaiveTime::MIN { None } else { Some(to) })
+        };
+        let is_valid = !working_hours.is_empty()
+            && working_hours
+                .iter()
+                .map(ends)
+                .all(|(from, to)| to.is_none_or(|to| from < to))
+            && working_hours
+                .windows(2)
+                .all(|w| ends(&w[0]).1.is_some_and(|to| to <= w[1].0));
+        if !is_valid {
+            return Err(());
+        }
+        self.working_hours = working_hours;
+        Ok(())
+    }
+
+    /// Time worked on a working day, when clocks don't change.
+    pub fn hours_per_day(&self) -> TimeDelta {
+        self.working_hours
+            .iter()
+            .map(|&(from, to)| {
+                if to == NaiveTime::MIN {
+                    TimeDelta::days(1) - (from - NaiveTime::MIN)
+                } else {
+                    to - from
+                }
+            })
+            .sum()
+    }
+
+    pub fn time_zone(&self) -> Tz {
+        self.time_zone
+    }
+
+    pub fn set_time_zone(&mut self, time_zone: Tz) {
+        self.time_zone = time_zone;
+    }
+
+    /// Wall-clock time of `date` in the time zone of the calendar.
+    pub fn to_local(&self, date: DateTime<Utc>) -> NaiveDateTime {
+        date.with_timezone(&self.time_zone).naive_local()
+    }
+
+    /// The date shown as `local` in the time zone of the calendar. When clocks
+    /// go back, times seen twice are the first ones. When they go forward,
+    /// skipped times are moved forward as much as the clocks.
+    pub fn to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self.time_zone.from_local_datetime(&local) {
+            LocalResult::Single(date) | LocalResult::Ambiguous(date, _) => date.with_timezone(&Utc),
+            LocalResult::None => {
+                // Clocks change at most once a day.
+                let offset = self
+                    .time_zone
+                    .offset_from_utc_datetime(&(local - TimeDelta::days(1)))
+                    .fix();
+                (local - TimeDelta::seconds(offset.local_minus_utc() as i64)).and_utc()
+            }
+        }
+    }
+
+    /// When work starts on `day`, were it a working day.
+    pub fn day_start(&self, day: NaiveDate) -> DateTime<Utc> {
+        self.periods(day)
+            .next()
+            .expect("Calendars should have working hours. This is a bug.")
+            .0
+    }
+
+    /// When work ends on `day`, were it a working day.
+    pub fn day_finish(&self, day: NaiveDate) -> DateTime<Utc> {
+        self.periods(day)
+            .next_back()
+            .expect("Calendars should have working hours. This is a bug.")
+            .1
+    }
+
+    pub fn holidays(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.holidays.iter().copied()
+    }
+
+    pub fn add_holiday(&mut self, date: NaiveDate) {
+        self.holidays.insert(date);
+    }
+
+    pub fn remove_holiday(&mut self, date: NaiveDate) {
+        self.holidays.remove(&date);
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.works_on(date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// The date `work` of working time after `date`, or before it if `work`
+    /// is negative.
+    pub fn add(&self, date: DateTime<Utc>, work: TimeDelta) -> DateTime<Utc> {
+        if work == TimeDelta::zero() {
+            return date;
+        }
+
+        let is_forward = work > TimeDelta::zero();
+        let mut left = work.abs();
+        let mut day = self.to_local(date).date();
+        loop {
+            if self.is_working_day(day) {
+                let periods = self.periods(day).collect::<Vec<_>>();
+                if is_forward {
+                    for (from, to) in periods {
+                        let from = from.max(date);
+                        if from >= to {
+                            continue;
+                        }
+                        if left <= to - from {
+                            return from + left;
+                        }
+                        left -= to - from;
+                    }
+                } else {
+                    for (from, to) in periods.into_iter().rev() {
+                        let to = to.min(date);
+                        if from >= to {
+                            continue;
+                        }
+                        if left <= to - from {
+                            return to - left;
+                        }
+                        left -= to - from;
+                    }
+                }
+            }
+            day = if is_forward {
+                day.succ_opt()
+            } else {
+                day.pred_opt()
+            }
+            .expect("Schedules shouldn't reach the limits of chrono. This is a bug.");
+        }
+    }
+
+    /// The first working time from `date` on.
+    pub fn next_working_time(&self, date: DateTime<Utc>) -> DateTime<Utc> {
+        self.add(date, TimeDelta::minutes(1)) - TimeDelta::minutes(1)
+    }
+
+    /// Working time between `from` and `to`, negative if `to` comes first.
+    pub fn working_time(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> TimeDelta {
+        if to < from {
+            return -self.working_time(to, from);
+        }
+
+        let last_day = self.to_local(to).date();
+        self.to_local(from)
+            .date()
+            .iter_days()
+            .take_while(|day| *day <= last_day)
+            .filter(|&day| self.is_working_day(day))
+            .flat_map(|day| self.periods(day))
+            .map(|(start, finish)| (start.max(from), finish.min(to)))
+            .filter(|(start, finish)| start < finish)
+            .map(|(start, finish)| finish - start)
+            .sum()
+    }
+
+    /// Periods worked on `day`, were it a working day.
+    fn periods(
+        &self,
+        day: NaiveDate,
+    ) -> impl DoubleEndedIterator<Item = (DateTime<Utc>, DateTime<Utc>)> + '_ {
+        self.working_hours.iter().map(move |&(from, to)| {
+            let to = if to == NaiveTime::MIN {
+                day.succ_opt()
+                    .expect("Schedules shouldn't reach the limits of chrono. This is a bug.")
+                    .and_time(to)
+            } else {
+                day.and_time(to)
+            };
+            (self.to_utc(day.and_time(from)), self.to_utc(to))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+    use chrono_tz::Tz;
+
+    use super::Calendar;
+
+    fn date(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn work_skips_nights_and_weekends() {
+        let calendar = Calendar::default();
+        // A Friday afternoon.
+        let start = date("2025-01-03T13:00:00Z");
+
+        let finish = calendar.add(start, TimeDelta::hours(16));
+
+        assert_eq!(finish, date("2025-01-07T12:00:00Z"));
+        assert_eq!(calendar.add(finish, TimeDelta::hours(-16)), start);
+        assert_eq!(calendar.working_time(start, finish), TimeDelta::hours(16));
+    }
+
+    #[test]
+    fn holidays_are_not_worked() {
+        let mut calendar = Calendar::default();
+        calendar.add_holiday(NaiveDate::from_ymd_opt(2025, 1, 6).unwrap());
+
+        assert_eq!(
+            calendar.add(date("2025-01-03T17:00:00Z"), TimeDelta::hours(8)),
+            date("2025-01-07T17:00:00Z")
+        );
+        assert_eq!(
+            calendar.next_working_time(date("2025-01-04T10:00:00Z")),
+            date("2025-01-07T08:00:00Z")
+        );
+    }
+
+    #[test]
+    fn continuous_calendars_count_elapsed_time() {
+        let calendar = Calendar::continuous();
+        let start = date("2025-01-04T22:00:00Z");
+
+        assert_eq!(
+            calendar.add(start, TimeDelta::hours(5)),
+            date("2025-01-05T03:00:00Z")
+        );
+        assert_eq!(calendar.hours_per_day(), TimeDelta::hours(24));
+    }
+
+    #[test]
+    fn working_hours_follow_clock_changes() {
+        let mut calendar = Calendar::default();
+        calendar.set_time_zone(Tz::Europe__Rome);
+        // Clocks go forward on Sunday, March 30th 2025, from UTC+1 to UTC+2.
+        // A Friday at 13:00 in Rome.
+        let start = date("2025-03-28T12:00:00Z");
+
+        let finish = calendar.add(start, TimeDelta::hours(8));
+
+        assert_eq!(finish, date("2025-03-31T10:00:00Z"));
+        assert_eq!(calendar.to_local(finish).to_string(), "2025-03-31 12:00:00");
+        assert_eq!(calendar.working_time(start, finish), TimeDelta::hours(8));
+        assert_eq!(
+            calendar.to_utc("2025-03-30T02:30:00".parse().unwrap()),
+            date("2025-03-30T01:30:00Z")
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Dates as they're typed in the grids.
+//!
+//! Besides the format dates are shown in, cells take ISO 8601 dates, dates
+//! with dots (`27.05.2025`, day first), slashes (`05/27/2025`, month first)
+//! or month names (`27 May 2025`, `May 27, 2025`), and relative dates such as
+//! `today`, `+3d` or `next monday`. Any of them can be followed by a time.
+//! Dates are in the time zone of the project, unless they give an offset.
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+
+use crate::calendar::Calendar;
+
+/// Format dates are shown in.
+pub const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+const DATE_TIME_FORMATS: [&str; 11] = [
+    DATE_FORMAT,
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%m/%d/%Y %H:%M",
+    "%d %b %Y %H:%M",
+    "%b %d, %Y %H:%M",
+    "%b %d %Y %H:%M",
+    "%Y/%m/%d %H:%M",
+    "%Y%m%dT%H%M%S",
+];
+const DATE_ONLY_FORMATS: [&str; 7] = [
+    "%Y-%m-%d",
+    "%d.%m.%Y",
+    "%m/%d/%Y",
+    "%d %b %Y",
+    "%b %d, %Y",
+    "%b %d %Y",
+    "%Y/%m/%d",
+];
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Parses a date typed in a cell, in the time zone of `calendar`. Relative
+/// dates count from `now`, and dates without a time take the one
+/// `at_default_time` gives their day.
+pub fn parse(
+    s: &str,
+    now: DateTime<Utc>,
+    calendar: &Calendar,
+    at_default_time: impl Fn(NaiveDate) -> DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+
+    DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .map(|date| calendar.to_utc(date))
+        .or_else(|| {
+            DATE_ONLY_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+                .map(&at_default_time)
+        })
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|date| date.with_timezone(&Utc))
+        })
+        .or_else(|| parse_relative(s, now, calendar, &at_default_time))
+}
+
+/// Parses `now`, or a relative day such as `today`, `tomorrow`, `+3d`, `-2w`,
+/// `next monday` or `last friday`, optionally followed by a time.
+fn parse_relative(
+    s: &str,
+    now: DateTime<Utc>,
+    calendar: &Calendar,
+    at_default_time: impl Fn(NaiveDate) -> DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let s = s.to_ascii_lowercase();
+    if s == "now" {
+        return Some(now);
+    }
+
+    let mut words = s.split_whitespace().collect::<Vec<&str>>();
+    let time = words
+        .last()
+        .and_then(|word| NaiveTime::parse_from_str(word, TIME_FORMAT).ok());
+    if time.is_some() {
+        words.pop();
+    }
+
+    let today = calendar.to_local(now).date();
+    let day = match words.as_slice() {
+        ["today"] => today,
+        ["tomorrow"] => today.succ_opt()?,
+        ["yesterday"] => today.pred_opt()?,
+        ["next", weekday] => {
+            let weekday = weekday.parse::<Weekday>().ok()?;
+            (1..=7)
+                .filter_map(|n| today.checked_add_days(Days::new(n)))
+                .find(|day| day.weekday() == weekday)?
+        }
+        ["last", weekday] => {
+            let weekday = weekday.parse::<Weekday>().ok()?;
+            (1..=7)
+                .filter_map(|n| today.checked_sub_days(Days::new(n)))
+                .find(|day| day.weekday() == weekday)?
+        }
+        [offset] => {
+            let (count, days_per_count) = if let Some(count) = offset.strip_suffix('d') {
+                (count, 1)
+            } else {
+                (offset.strip_suffix('w')?, 7)
+            };
+            if !count.starts_with(['+', '-']) {
+                return None;
+            }
+            let days = count.parse::<i64>().ok()?.checked_mul(days_per_count)?;
+            let offset = Days::new(days.unsigned_abs());
+            if days < 0 {
+                today.checked_sub_days(offset)?
+            } else {
+                today.checked_add_days(offset)?
+            }
+        }
+        _ => return None,
+    };
+
+    Some(match time {
+        Some(time) => calendar.to_utc(day.and_time(time)),
+        None => at_default_time(day),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use chrono_tz::Tz;
+
+    use super::parse;
+    use crate::calendar::Calendar;
+
+    #[test]
+    fn dates_can_be_typed_in_many_ways() {
+        let date = |s: &str| {
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+                .unwrap()
+                .and_utc()
+        };
+        // A Tuesday.
+        let now = date("2025-05-27 10:20");
+        let calendar = Calendar::default();
+        let in_utc = |s: &str| parse(s, now, &calendar, |day| calendar.day_start(day));
+
+        assert_eq!(in_utc("2025-05-27 10:20"), Some(now));
+        assert_eq!(in_utc("2025-05-27T10:20:00"), Some(now));
+        assert_eq!(in_utc("2025-05-27T10:20:00+00:00"), Some(now));
+        assert_eq!(in_utc("27.05.2025"), Some(date("2025-05-27 08:00")));
+        assert_eq!(in_utc("05/27/2025 10:20"), Some(now));
+        assert_eq!(in_utc("May 27, 2025"), Some(date("2025-05-27 08:00")));
+        assert_eq!(in_utc("27 may 2025 10:20"), Some(now));
+        assert_eq!(in_utc("Now"), Some(now));
+        assert_eq!(in_utc("today"), Some(date("2025-05-27 08:00")));
+        assert_eq!(in_utc("tomorrow 14:00"), Some(date("2025-05-28 14:00")));
+        assert_eq!(in_utc("+3d"), Some(date("2025-05-30 08:00")));
+        assert_eq!(in_utc("-2w 9:30"), Some(date("2025-05-13 09:30")));
+        assert_eq!(in_utc("next monday"), Some(date("2025-06-02 08:00")));
+        assert_eq!(in_utc("last tue"), Some(date("2025-05-20 08:00")));
+        assert_eq!(in_utc("1993-27-05 10:20"), None);
+        assert_eq!(in_utc("3d"), None);
+        assert_eq!(in_utc("next month"), None);
+
+        let mut rome = Calendar::default();
+        rome.set_time_zone(Tz::Europe__Rome);
+        let in_rome = |s: &str| parse(s, now, &rome, |day| rome.day_start(day));
+        assert_eq!(in_rome("2025-05-27 10:20"), Some(date("2025-05-27 08:20")));
+        assert_eq!(
+            in_rome("2025-05-27T10:20:00-04:00"),
+            Some(date("2025-05-27 14:20"))
+        );
+        assert_eq!(in_rome("tomorrow"), Some(date("2025-05-28 06:00")));
+        assert_eq!(in_rome("now"), Some(now));
+    }
+}
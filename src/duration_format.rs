@@ -0,0 +1,190 @@
+//! Durations as they're typed and shown, such as `3d`, `2w` or `1w 2d 4h`.
+//!
+//! Days and weeks are amounts of working time: with the default 8 hours per
+//! day and 5 days per week, `1w` is 40 hours. As in MS Project, these
+//! conversions are settings of the project, apart from its calendar.
+
+use std::fmt::Display;
+
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DurationUnit {
+    Minutes,
+    #[default]
+    Hours,
+    Days,
+    Weeks,
+}
+
+impl DurationUnit {
+    pub const ALL: [DurationUnit; 4] = [
+        DurationUnit::Minutes,
+        DurationUnit::Hours,
+        DurationUnit::Days,
+        DurationUnit::Weeks,
+    ];
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            DurationUnit::Minutes => "m",
+            DurationUnit::Hours => "h",
+            DurationUnit::Days => "d",
+            DurationUnit::Weeks => "w",
+        }
+    }
+}
+
+impl Display for DurationUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DurationUnit::Minutes => f.write_str("Minutes"),
+            DurationUnit::Hours => f.write_str("Hours"),
+            DurationUnit::Days => f.write_str("Days"),
+            DurationUnit::Weeks => f.write_str("Weeks"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationFormat {
+    /// Unit durations are shown in, and typed in when they have no unit.
+    pub unit: DurationUnit,
+    minutes_per_day: i64,
+    days_per_week: u8,
+}
+
+impl Default for DurationFormat {
+    fn default() -> Self {
+        DurationFormat {
+            unit: DurationUnit::Hours,
+            minutes_per_day: 8 * 60,
+            days_per_week: 5,
+        }
+    }
+}
+
+impl DurationFormat {
+    pub fn hours_per_day(&self) -> TimeDelta {
+        TimeDelta::minutes(self.minutes_per_day)
+    }
+
+    /// Fails unless it's at least a minute and at most a whole day.
+    pub fn set_hours_per_day(&mut self, hours_per_day: TimeDelta) -> Result<(), ()> {
+        if hours_per_day < TimeDelta::minutes(1) || hours_per_day > TimeDelta::days(1) {
+            return Err(());
+        }
+        self.minutes_per_day = hours_per_day.num_minutes();
+        Ok(())
+    }
+
+    pub fn days_per_week(&self) -> u8 {
+        self.days_per_week
+    }
+
+    /// Fails unless it's between 1 and 7.
+    pub fn set_days_per_week(&mut self, days_per_week: u8) -> Result<(), ()> {
+        if !(1..=7).contains(&days_per_week) {
+            return Err(());
+        }
+        self.days_per_week = days_per_week;
+        Ok(())
+    }
+
+    fn minutes_per(&self, unit: DurationUnit) -> i64 {
+        match unit {
+            DurationUnit::Minutes => 1,
+            DurationUnit::Hours => 60,
+            DurationUnit::Days => self.minutes_per_day,
+            DurationUnit::Weeks => self.minutes_per_day * self.days_per_week as i64,
+        }
+    }
+
+    /// Parses a duration such as `1w 2d 4h`, `1.5d`, `90m` or `48 hours`. A
+    /// lone number is in [`DurationFormat::unit`].
+    pub fn parse(&self, s: &str) -> Option<TimeDelta> {
+        let mut rest = s.trim();
+        if rest.is_empty() {
+            return None;
+        }
+
+        let mut minutes = 0.;
+        let mut is_first = true;
+        while !rest.is_empty() {
+            let number_len = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let number = rest[..number_len].parse::<f64>().ok()?;
+            rest = rest[number_len..].trim_start();
+            let unit_len = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let unit = match rest[..unit_len].to_ascii_lowercase().as_str() {
+                "" if is_first && rest.is_empty() => self.unit,
+                "m" | "min" | "mins" | "minute" | "minutes" => DurationUnit::Minutes,
+                "h" | "hr" | "hrs" | "hour" | "hours" => DurationUnit::Hours,
+                "d" | "day" | "days" => DurationUnit::Days,
+                "w" | "wk" | "wks" | "week" | "weeks" => DurationUnit::Weeks,
+                _ => return None,
+            };
+            rest = rest[unit_len..].trim_start();
+            minutes += number * self.minutes_per(unit) as f64;
+            is_first = false;
+        }
+
+        // Far beyond any schedule, but within what TimeDelta holds.
+        (minutes <= 1e12).then(|| TimeDelta::minutes(minutes.round() as i64))
+    }
+
+    /// Formats a duration in [`DurationFormat::unit`], with what doesn't make
+    /// a whole unit in smaller units, like `1w 2d 4h`.
+    pub fn format(&self, duration: TimeDelta) -> String {
+        let mut left = duration.num_minutes();
+        let parts = DurationUnit::ALL
+            .into_iter()
+            .rev()
+            .skip_while(|&unit| unit != self.unit)
+            .filter_map(|unit| {
+                let count = left / self.minutes_per(unit);
+                left %= self.minutes_per(unit);
+                (count != 0).then(|| format!("{count}{}", unit.symbol()))
+            })
+            .collect::<Vec<String>>();
+
+        if parts.is_empty() {
+            format!("0{}", self.unit.symbol())
+        } else {
+            parts.join(" ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::{DurationFormat, DurationUnit};
+
+    #[test]
+    fn durations_mix_units() {
+        let mut format = DurationFormat::default();
+
+        assert_eq!(format.parse("1w 2d 4h"), Some(TimeDelta::hours(60)));
+        assert_eq!(format.parse("90m"), Some(TimeDelta::minutes(90)));
+        assert_eq!(format.parse("1.5d"), Some(TimeDelta::hours(12)));
+        assert_eq!(format.parse("48 h"), Some(TimeDelta::hours(48)));
+        assert_eq!(format.parse("7h30m"), Some(TimeDelta::minutes(450)));
+        assert_eq!(format.parse("40"), Some(TimeDelta::hours(40)));
+        assert_eq!(format.parse("4 2h"), None);
+        assert_eq!(format.parse("3 fortnights"), None);
+        assert_eq!(format.format(TimeDelta::minutes(450)), "7h 30m");
+
+        format.unit = DurationUnit::Weeks;
+        format.set_hours_per_day(TimeDelta::hours(10)).unwrap();
+        assert_eq!(format.parse("2"), Some(TimeDelta::hours(100)));
+        assert_eq!(format.format(TimeDelta::hours(1048)), "20w 4d 8h");
+        assert_eq!(format.format(TimeDelta::zero()), "0w");
+        assert!(format.set_days_per_week(8).is_err());
+    }
+}
@@ -0,0 +1,681 @@
+//! What PlanTer tracks about a project on top of what planter-core stores.
+//!
+//! Extras are kept by task index, in the same order as the tasks of the
+//! project, and must be told when a task or a resource is removed so that
+//! indices keep matching. Tasks and resources are shown by their [`Ids`]
+//! instead, which don't change when others are removed.
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+
+use crate::{
+    calendar::Calendar,
+    duration_format::DurationFormat,
+    scheduling::{Constraint, ConstraintKind, Link},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct ProjectExtras {
+    tasks: Vec<TaskExtras>,
+    /// Working time of the project, which durations are counted in.
+    calendar: Calendar,
+    /// Days off of people, by resource index, on top of the holidays of the
+    /// project.
+    days_off: BTreeMap<usize, BTreeSet<NaiveDate>>,
+    /// How durations are typed and shown, and how long days and weeks are.
+    duration_format: DurationFormat,
+    task_ids: Ids,
+    resource_ids: Ids,
+}
+
+/// Stable IDs of tasks or resources, by index. IDs are never given twice, so
+/// an ID keeps pointing at the same task or resource when others are removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ids {
+    /// IDs given so far. Items past the end take the IDs from `next` on, in
+    /// order, so that items added to the project get an ID right away.
+    given: Vec<usize>,
+    next: usize,
+}
+
+impl Ids {
+    /// Takes the IDs of items in order, and the next ID to give. Fails if an
+    /// ID is given twice, or isn't below `next`.
+    pub fn new(given: Vec<usize>, next: usize) -> Result<Self, ()> {
+        let unique = given.iter().collect::<BTreeSet<_>>();
+        if unique.len() < given.len() || given.iter().any(|&id| id >= next) {
+            return Err(());
+        }
+        Ok(Ids { given, next })
+    }
+
+    pub fn id(&self, index: usize) -> usize {
+        match self.given.get(index) {
+            Some(&id) => id,
+            None => self.next + index - self.given.len(),
+        }
+    }
+
+    /// Index of the item with `id`, if it's one of the first `count` items.
+    pub fn index(&self, id: usize, count: usize) -> Option<usize> {
+        let index = match self.given.iter().position(|&i| i == id) {
+            Some(index) => index,
+            None if id >= self.next => self.given.len() + id - self.next,
+            None => return None,
+        };
+        (index < count).then_some(index)
+    }
+
+    /// IDs of the first `count` items, and the next ID to give after them.
+    pub fn to_vec(&self, count: usize) -> (Vec<usize>, usize) {
+        let ids = (0..count).map(|i| self.id(i)).collect::<Vec<_>>();
+        let next = self.next.max(self.id(count));
+        (ids, next)
+    }
+
+    fn remove(&mut self, index: usize) {
+        while self.given.len() <= index {
+            self.given.push(self.next);
+            self.next += 1;
+        }
+        self.given.remove(index);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskExtras {
+    /// Type and lag of the links from the predecessors of the task, by
+    /// predecessor index. Links missing here are finish-to-start without lag.
+    links: BTreeMap<usize, Link>,
+    /// The summary task this task is nested under, in the work breakdown
+    /// structure.
+    parent: Option<usize>,
+    /// Duration of the task while it has no start, to the minute, since
+    /// planter-core only keeps whole hours.
+    unscheduled_duration: Option<TimeDelta>,
+    /// Resources assigned to the task, by resource index. planter-core has no
+    /// assignments, so they're only kept here:
+    /// [`ProjectExtras::remove_resource`] drops and shifts them when a
+    /// resource is removed from the project.
+    assignments: BTreeMap<usize, Assignment>,
+    /// Whether the work of the task stays the same when resources are
+    /// assigned or unassigned, so that more resources finish it sooner.
+    effort_driven: bool,
+    /// Whether the task marks a point in time, rather than some work.
+    milestone: bool,
+    /// Duration the task had before it was made a milestone, which it gets
+    /// back when it stops being one.
+    milestone_duration: Option<TimeDelta>,
+    progress: Progress,
+    constraint: Option<Constraint>,
+    /// When the task should be finished by, whatever its constraint.
+    deadline: Option<DateTime<Utc>>,
+}
+
+/// How far along a task is. Tasks are completed when they're 100% complete.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub percent_complete: u8,
+    pub actual_start: Option<DateTime<Utc>>,
+    pub actual_finish: Option<DateTime<Utc>>,
+}
+
+impl Progress {
+    /// Progress of a summary task, given the progress and the duration of
+    /// each of its children. Longer children weigh more, and milestones only
+    /// count if all the children are milestones.
+    pub fn roll_up(children: impl IntoIterator<Item = (Progress, TimeDelta)>) -> Progress {
+        let children = children.into_iter().collect::<Vec<_>>();
+        let total_minutes = children.iter().map(|(_, d)| d.num_minutes()).sum::<i64>();
+        let percent_complete = if total_minutes > 0 {
+            children
+                .iter()
+                .map(|(p, d)| p.percent_complete as i64 * d.num_minutes())
+                .sum::<i64>()
+                / total_minutes
+        } else if !children.is_empty() {
+            children
+                .iter()
+                .map(|(p, _)| p.percent_complete as i64)
+                .sum::<i64>()
+                / children.len() as i64
+        } else {
+            0
+        } as u8;
+
+        Progress {
+            percent_complete,
+            actual_start: children.iter().filter_map(|(p, _)| p.actual_start).min(),
+            actual_finish: (percent_complete == 100)
+                .then(|| children.iter().filter_map(|(p, _)| p.actual_finish).max())
+                .flatten(),
+        }
+    }
+}
+
+/// How much of a resource goes into a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assignment {
+    /// Share of the time of the resource spent on the task, in percent.
+    pub units: u16,
+    pub work: TimeDelta,
+}
+
+/// Units of a resource working full time on a task.
+pub const FULL_TIME_UNITS: u16 = 100;
+
+impl Default for Assignment {
+    fn default() -> Self {
+        Assignment {
+            units: FULL_TIME_UNITS,
+            work: TimeDelta::zero(),
+        }
+    }
+}
+
+impl Assignment {
+    /// The assignment that keeps the resource busy for `duration` at `units`.
+    pub fn for_duration(units: u16, duration: TimeDelta) -> Self {
+        Assignment {
+            units,
+            work: TimeDelta::minutes(
+                duration.num_minutes() * units as i64 / FULL_TIME_UNITS as i64,
+            ),
+        }
+    }
+
+    /// Time it takes the resource to complete its work.
+    pub fn duration(&self) -> TimeDelta {
+        TimeDelta::minutes(self.work.num_minutes() * FULL_TIME_UNITS as i64 / self.units as i64)
+    }
+}
+
+/// A task in outline order: parents come right before their children, which
+/// are sorted by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineEntry {
+    pub task: usize,
+    /// Number of ancestors of the task.
+    pub depth: usize,
+    /// Position of the task in the WBS, like `1.2.3`.
+    pub number: String,
+}
+
+impl ProjectExtras {
+    /// The link from `predecessor` to `successor`. It's only meaningful if the
+    /// project actually has such a link.
+    pub fn link(&self, predecessor: usize, successor: usize) -> Link {
+        self.tasks
+            .get(successor)
+            .and_then(|t| t.links.get(&predecessor))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_link(&mut self, predecessor: usize, successor: usize, link: Link) {
+        let links = &mut self.task_mut(successor).links;
+        if link == Link::default() {
+            links.remove(&predecessor);
+        } else {
+            links.insert(predecessor, link);
+        }
+    }
+
+    /// Forgets the links of `successor` from tasks that aren't `predecessors`.
+    pub fn retain_links(&mut self, successor: usize, predecessors: &[usize]) {
+        self.task_mut(successor)
+            .links
+            .retain(|p, _| predecessors.contains(p));
+    }
+
+    pub fn parent(&self, task: usize) -> Option<usize> {
+        self.tasks.get(task).and_then(|t| t.parent)
+    }
+
+    /// Nests `task` under `parent`, or at the top level if `None`. Fails if
+    /// `parent` is `task` itself or one of its descendants.
+    pub fn set_parent(&mut self, task: usize, parent: Option<usize>) -> Result<(), ()> {
+        if parent.is_some_and(|p| p == task || self.is_ancestor(task, p)) {
+            return Err(());
+        }
+        self.task_mut(task).parent = parent;
+        Ok(())
+    }
+
+    /// Whether `ancestor` is the parent of `task`, or the parent of its
+    /// parent, and so on.
+    pub fn is_ancestor(&self, ancestor: usize, task: usize) -> bool {
+        self.ancestors(task).any(|p| p == ancestor)
+    }
+
+    /// Parent of `task`, then the parent of its parent, and so on.
+    pub fn ancestors(&self, task: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.parent(task), |&p| self.parent(p))
+    }
+
+    /// Children of `task`, or top-level tasks if `None`, sorted by index.
+    pub fn children(&self, task: Option<usize>, task_count: usize) -> Vec<usize> {
+        (0..task_count)
+            .filter(|&i| self.parent(i) == task)
+            .collect()
+    }
+
+    pub fn is_summary(&self, task: usize, task_count: usize) -> bool {
+        (0..task_count).any(|i| self.parent(i) == Some(task))
+    }
+
+    pub fn outline(&self, task_count: usize) -> Vec<OutlineEntry> {
+        let mut outline = Vec::with_capacity(task_count);
+        self.push_outline(None, 0, "", task_count, &mut outline);
+        outline
+    }
+
+    fn push_outline(
+        &self,
+        parent: Option<usize>,
+        depth: usize,
+        prefix: &str,
+        task_count: usize,
+        outline: &mut Vec<OutlineEntry>,
+    ) {
+        for (position, task) in self.children(parent, task_count).into_iter().enumerate() {
+            let number = format!("{prefix}{}", position + 1);
+            outline.push(OutlineEntry {
+                task,
+                depth,
+                number: number.clone(),
+            });
+            self.push_outline(
+                Some(task),
+                depth + 1,
+                &format!("{number}."),
+                task_count,
+                outline,
+            );
+        }
+    }
+
+    /// Nests `task` under the sibling right above it, if any.
+    pub fn indent(&mut self, task: usize, task_count: usize) {
+        let siblings = self.children(self.parent(task), task_count);
+        let position = siblings
+            .iter()
+            .position(|&s| s == task)
+            .expect("A task should be among the children of its parent. This is a bug.");
+        if position > 0 {
+            self.set_parent(task, Some(siblings[position - 1]))
+                .expect("A sibling can't be a descendant. This is a bug.");
+        }
+    }
+
+    /// Moves `task` one level up, next to its parent.
+    pub fn outdent(&mut self, task: usize) {
+        if let Some(parent) = self.parent(task) {
+            self.set_parent(task, self.parent(parent))
+                .expect("An ancestor can't be a descendant. This is a bug.");
+        }
+    }
+
+    /// Resources assigned to `task`, by ascending resource index.
+    pub fn assignments(&self, task: usize) -> impl Iterator<Item = (usize, Assignment)> + '_ {
+        self.tasks
+            .get(task)
+            .into_iter()
+            .flat_map(|t| t.assignments.iter().map(|(&r, &a)| (r, a)))
+    }
+
+    /// Indices of the resources assigned to `task`, in ascending order.
+    pub fn resources(&self, task: usize) -> impl Iterator<Item = usize> + '_ {
+        self.assignments(task).map(|(r, _)| r)
+    }
+
+    pub fn set_assignments(&mut self, task: usize, assignments: &[(usize, Assignment)]) {
+        self.task_mut(task).assignments = assignments.iter().copied().collect();
+    }
+
+    /// Total work of the resources assigned to `task`.
+    pub fn work(&self, task: usize) -> TimeDelta {
+        self.assignments(task).map(|(_, a)| a.work).sum()
+    }
+
+    /// Splits `work` among the resources assigned to `task`, in proportion to
+    /// their units so that they all finish together.
+    pub fn set_work(&mut self, task: usize, work: TimeDelta) {
+        let assignments = &mut self.task_mut(task).assignments;
+        let total_units = assignments.values().map(|a| a.units as i64).sum::<i64>();
+        let mut left = work.num_minutes();
+        for (position, assignment) in assignments.values_mut().enumerate().rev() {
+            // The first assignment takes what's left after rounding.
+            let minutes = if position == 0 {
+                left
+            } else {
+                work.num_minutes() * assignment.units as i64 / total_units
+            };
+            assignment.work = TimeDelta::minutes(minutes);
+            left -= minutes;
+        }
+    }
+
+    /// Changes the work of the resources assigned to `task` so that they're
+    /// busy for `duration` at their current units.
+    pub fn fit_work_to_duration(&mut self, task: usize, duration: TimeDelta) {
+        for assignment in self.task_mut(task).assignments.values_mut() {
+            *assignment = Assignment::for_duration(assignment.units, duration);
+        }
+    }
+
+    /// Time it takes the resources assigned to `task` to complete their work,
+    /// if there is any work.
+    pub fn effort_duration(&self, task: usize) -> Option<TimeDelta> {
+        self.assignments(task)
+            .filter(|(_, a)| a.work > TimeDelta::zero())
+            .map(|(_, a)| a.duration())
+            .max()
+    }
+
+    pub fn unscheduled_duration(&self, task: usize) -> Option<TimeDelta> {
+        self.tasks.get(task).and_then(|t| t.unscheduled_duration)
+    }
+
+    pub fn set_unscheduled_duration(&mut self, task: usize, duration: Option<TimeDelta>) {
+        self.task_mut(task).unscheduled_duration = duration;
+    }
+
+    pub fn is_effort_driven(&self, task: usize) -> bool {
+        self.tasks.get(task).is_some_and(|t| t.effort_driven)
+    }
+
+    pub fn set_effort_driven(&mut self, task: usize, effort_driven: bool) {
+        self.task_mut(task).effort_driven = effort_driven;
+    }
+
+    pub fn is_milestone(&self, task: usize) -> bool {
+        self.tasks.get(task).is_some_and(|t| t.milestone)
+    }
+
+    pub fn set_milestone(&mut self, task: usize, milestone: bool) {
+        self.task_mut(task).milestone = milestone;
+    }
+
+    pub fn milestone_duration(&self, task: usize) -> Option<TimeDelta> {
+        self.tasks.get(task).and_then(|t| t.milestone_duration)
+    }
+
+    pub fn set_milestone_duration(&mut self, task: usize, duration: Option<TimeDelta>) {
+        self.task_mut(task).milestone_duration = duration;
+    }
+
+    pub fn constraint(&self, task: usize) -> Option<Constraint> {
+        self.tasks.get(task).and_then(|t| t.constraint)
+    }
+
+    /// Constraints to start as soon as possible hold the task to no date, and
+    /// are the same as no constraint.
+    pub fn set_constraint(&mut self, task: usize, constraint: Option<Constraint>) {
+        self.task_mut(task).constraint =
+            constraint.filter(|c| c.kind != ConstraintKind::AsSoonAsPossible);
+    }
+
+    pub fn deadline(&self, task: usize) -> Option<DateTime<Utc>> {
+        self.tasks.get(task).and_then(|t| t.deadline)
+    }
+
+    pub fn set_deadline(&mut self, task: usize, deadline: Option<DateTime<Utc>>) {
+        self.task_mut(task).deadline = deadline;
+    }
+
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    pub fn set_calendar(&mut self, calendar: Calendar) {
+        self.calendar = calendar;
+    }
+
+    pub fn duration_format(&self) -> &DurationFormat {
+        &self.duration_format
+    }
+
+    pub fn set_duration_format(&mut self, duration_format: DurationFormat) {
+        self.duration_format = duration_format;
+    }
+
+    /// Days off of the person at resource index `resource`, in ascending
+    /// order.
+    pub fn days_off(&self, resource: usize) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.days_off.get(&resource).into_iter().flatten().copied()
+    }
+
+    pub fn set_days_off(&mut self, resource: usize, days_off: BTreeSet<NaiveDate>) {
+        if days_off.is_empty() {
+            self.days_off.remove(&resource);
+        } else {
+            self.days_off.insert(resource, days_off);
+        }
+    }
+
+    /// Working time of `task`: the calendar of the project, without the days
+    /// off of the people assigned to it.
+    pub fn task_calendar(&self, task: usize) -> Cow<'_, Calendar> {
+        let mut days_off = self
+            .resources(task)
+            .flat_map(|r| self.days_off(r))
+            .peekable();
+        if days_off.peek().is_none() {
+            return Cow::Borrowed(&self.calendar);
+        }
+
+        let mut calendar = self.calendar.clone();
+        for day in days_off {
+            calendar.add_holiday(day);
+        }
+        Cow::Owned(calendar)
+    }
+
+    pub fn progress(&self, task: usize) -> Progress {
+        self.tasks.get(task).map(|t| t.progress).unwrap_or_default()
+    }
+
+    pub fn set_progress(&mut self, task: usize, progress: Progress) {
+        self.task_mut(task).progress = progress;
+    }
+
+    pub fn task_ids(&self) -> &Ids {
+        &self.task_ids
+    }
+
+    pub fn set_task_ids(&mut self, task_ids: Ids) {
+        self.task_ids = task_ids;
+    }
+
+    pub fn resource_ids(&self) -> &Ids {
+        &self.resource_ids
+    }
+
+    pub fn set_resource_ids(&mut self, resource_ids: Ids) {
+        self.resource_ids = resource_ids;
+    }
+
+    /// Unassigns the resource at `index` from every task, and shifts the
+    /// following ones down by one.
+    pub fn remove_resource(&mut self, index: usize) {
+        for task in &mut self.tasks {
+            task.assignments = std::mem::take(&mut task.assignments)
+                .into_iter()
+                .filter(|&(r, _)| r != index)
+                .map(|(r, a)| (if r > index { r - 1 } else { r }, a))
+                .collect();
+        }
+        self.days_off = std::mem::take(&mut self.days_off)
+            .into_iter()
+            .filter(|&(r, _)| r != index)
+            .map(|(r, days)| (if r > index { r - 1 } else { r }, days))
+            .collect();
+        self.resource_ids.remove(index);
+    }
+
+    /// Forgets the task at `index`, shifting the following ones down by one.
+    /// Its children move up to its parent.
+    pub fn remove_task(&mut self, index: usize) {
+        let parent = self.parent(index);
+        if index < self.tasks.len() {
+            self.tasks.remove(index);
+        }
+        let shift = |i: usize| if i > index { i - 1 } else { i };
+        for task in &mut self.tasks {
+            task.parent = match task.parent {
+                Some(p) if p == index => parent.map(shift),
+                p => p.map(shift),
+            };
+            task.links = std::mem::take(&mut task.links)
+                .into_iter()
+                .filter(|(p, _)| *p != index)
+                .map(|(p, link)| (shift(p), link))
+                .collect();
+        }
+        self.task_ids.remove(index);
+    }
+
+    fn task_mut(&mut self, index: usize) -> &mut TaskExtras {
+        if index >= self.tasks.len() {
+            self.tasks.resize_with(index + 1, TaskExtras::default);
+        }
+        &mut self.tasks[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use chrono::{NaiveDate, TimeDelta};
+
+    use super::{Assignment, Ids, Progress, ProjectExtras};
+    use crate::scheduling::{Link, LinkKind};
+
+    #[test]
+    fn removing_a_task_shifts_links() {
+        let link = Link {
+            kind: LinkKind::StartToStart,
+            lag: TimeDelta::hours(2),
+        };
+        let mut extras = ProjectExtras::default();
+        extras.set_link(0, 2, link);
+        extras.set_link(1, 3, link);
+
+        extras.remove_task(1);
+
+        assert_eq!(extras.link(0, 1), link);
+        assert_eq!(extras.link(1, 2), Link::default());
+    }
+
+    #[test]
+    fn ids_are_kept_when_tasks_are_removed() {
+        let mut extras = ProjectExtras::default();
+        extras.remove_task(1);
+        extras.remove_task(2);
+
+        let ids = extras.task_ids();
+        assert_eq!(ids.to_vec(3), (vec![0, 2, 4], 5));
+        assert_eq!(ids.index(4, 3), Some(2));
+        assert_eq!(ids.index(5, 4), Some(3));
+        assert_eq!(ids.index(1, 3), None);
+        assert_eq!(ids.index(5, 3), None);
+        assert!(Ids::new(vec![0, 2, 2], 3).is_err());
+    }
+
+    #[test]
+    fn outline_numbers_follow_nesting() {
+        let mut extras = ProjectExtras::default();
+        for task in [1, 2, 3, 2, 3] {
+            extras.indent(task, 5);
+        }
+        extras.outdent(3);
+
+        let numbers = extras
+            .outline(5)
+            .into_iter()
+            .map(|entry| (entry.task, entry.number))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            numbers,
+            [(0, "1"), (1, "1.1"), (2, "1.1.1"), (3, "1.2"), (4, "2")]
+                .map(|(task, number)| (task, number.to_owned()))
+        );
+        assert!(extras.set_parent(0, Some(2)).is_err());
+    }
+
+    #[test]
+    fn removing_a_summary_keeps_its_children() {
+        let mut extras = ProjectExtras::default();
+        extras.set_parent(1, Some(0)).unwrap();
+        extras.set_parent(2, Some(1)).unwrap();
+
+        extras.remove_task(1);
+
+        assert_eq!(extras.parent(1), Some(0));
+    }
+
+    #[test]
+    fn removing_a_resource_shifts_assignments() {
+        let mut extras = ProjectExtras::default();
+        extras.set_assignments(0, &[(0, Assignment::default()), (2, Assignment::default())]);
+        extras.set_assignments(1, &[(1, Assignment::default())]);
+        let day_off = NaiveDate::from_ymd_opt(2025, 8, 15).unwrap();
+        extras.set_days_off(2, BTreeSet::from([day_off]));
+
+        extras.remove_resource(1);
+
+        assert_eq!(extras.resources(0).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(extras.resources(1).count(), 0);
+        assert_eq!(extras.days_off(1).collect::<Vec<_>>(), vec![day_off]);
+        assert!(!extras.task_calendar(0).is_working_day(day_off));
+        assert!(extras.task_calendar(1).is_working_day(day_off));
+    }
+
+    #[test]
+    fn more_units_finish_the_same_work_sooner() {
+        let mut extras = ProjectExtras::default();
+        let half_time = Assignment {
+            units: 50,
+            ..Default::default()
+        };
+        extras.set_assignments(0, &[(0, Assignment::default())]);
+        extras.set_work(0, TimeDelta::hours(40));
+        assert_eq!(extras.effort_duration(0), Some(TimeDelta::hours(40)));
+
+        extras.set_assignments(0, &[(0, Assignment::default()), (1, half_time)]);
+        extras.set_work(0, TimeDelta::hours(40));
+
+        assert_eq!(extras.work(0), TimeDelta::hours(40));
+        assert_eq!(extras.effort_duration(0), Some(TimeDelta::minutes(1600)));
+
+        extras.fit_work_to_duration(0, TimeDelta::hours(10));
+        assert_eq!(extras.work(0), TimeDelta::hours(15));
+    }
+
+    #[test]
+    fn progress_rolls_up_by_duration() {
+        let done = Progress {
+            percent_complete: 100,
+            ..Default::default()
+        };
+
+        let progress = Progress::roll_up([
+            (done, TimeDelta::hours(30)),
+            (Progress::default(), TimeDelta::hours(10)),
+            (done, TimeDelta::zero()),
+        ]);
+
+        assert_eq!(progress.percent_complete, 75);
+        assert_eq!(progress.actual_finish, None);
+        assert_eq!(
+            Progress::roll_up([(done, TimeDelta::zero())]).percent_complete,
+            100
+        );
+    }
+}
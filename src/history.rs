@@ -0,0 +1,104 @@
+//! Undo and redo of the changes made to a project.
+//!
+//! The history keeps whole snapshots of the project as it was before each
+//! change, rather than the inverse of every edit, so that undoing restores
+//! exactly what the pages showed, including everything a change rippled
+//! through, such as the links of a deleted task.
+
+/// Changes that can be undone, beyond which the oldest ones are forgotten.
+const MAX_STEPS: usize = 100;
+
+#[derive(Debug)]
+pub struct History<T, K> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    /// Cell the last change was typed into, so that typing on in the same
+    /// cell is undone at once, rather than one keystroke at a time.
+    typed_cell: Option<K>,
+}
+
+impl<T, K> Default for History<T, K> {
+    fn default() -> Self {
+        History {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            typed_cell: None,
+        }
+    }
+}
+
+impl<T, K: PartialEq> History<T, K> {
+    /// Records a change, given the snapshot from `before` it and the cell it
+    /// was typed into, if any. Changes that can be redone are forgotten.
+    pub fn record(&mut self, before: T, typed_cell: Option<K>) {
+        self.redo.clear();
+        let is_typing_on = self.is_typing_on(typed_cell.as_ref());
+        self.typed_cell = typed_cell;
+        if is_typing_on {
+            return;
+        }
+
+        self.undo.push(before);
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Whether typing in `typed_cell` goes on with the last change, and so
+    /// would be undone with it.
+    pub fn is_typing_on(&self, typed_cell: Option<&K>) -> bool {
+        typed_cell.is_some() && typed_cell == self.typed_cell.as_ref()
+    }
+
+    /// Returns the snapshot to go back to, if any, keeping the `current` one
+    /// to redo.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        self.typed_cell = None;
+        Some(previous)
+    }
+
+    /// Returns the snapshot to go forward to, if any, keeping the `current`
+    /// one to undo.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        self.typed_cell = None;
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    #[test]
+    fn typing_in_a_cell_is_undone_at_once() {
+        let mut history = History::default();
+        history.record("", Some("name"));
+        history.record("B", Some("name"));
+        history.record("Bu", Some("name"));
+        history.record("Build", None);
+
+        assert_eq!(history.undo("Built"), Some("Build"));
+        assert_eq!(history.undo("Build"), Some(""));
+        assert_eq!(history.undo(""), None);
+        assert_eq!(history.redo(""), Some("Build"));
+        assert!(history.can_redo());
+
+        history.record("Build", Some("name"));
+        assert!(!history.can_redo());
+        assert!(history.is_typing_on(Some(&"name")));
+        assert!(!history.is_typing_on(None));
+        assert_eq!(history.undo("Builds"), Some("Build"));
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt::Display;
+
+use planter_core::{
+    project::Project,
+    resources::{Material, NonConsumable, Resource},
+};
+
+pub mod csv;
+pub mod ics;
+pub mod mspdi;
+pub mod native;
+
+#[derive(Debug, Clone)]
+pub enum FileError {
+    /// The file couldn't be read or written.
+    Io(String),
+    /// The file isn't in the expected format.
+    Malformed(String),
+    /// The file was written by a newer release of PlanTer.
+    UnsupportedVersion(u32),
+    /// The file is well formed, but its content can't be loaded in a project.
+    Invalid(String),
+}
+
+impl Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Io(e) => write!(f, "I/O error: {e}"),
+            FileError::Malformed(e) => write!(f, "Malformed file: {e}"),
+            FileError::UnsupportedVersion(v) => write!(
+                f,
+                "The project was saved with format version {v}, but only versions up to {} are supported",
+                native::FORMAT_VERSION
+            ),
+            FileError::Invalid(e) => write!(f, "Invalid project: {e}"),
+        }
+    }
+}
+
+/// Adds a material to the project and returns its resource index.
+fn add_material(
+    project: &mut Project,
+    name: &str,
+    is_consumable: bool,
+    quantity: Option<u16>,
+    cost: Option<u16>,
+) -> usize {
+    let material = Material::NonConsumable(NonConsumable::new(name));
+    project.add_resource(Resource::Material(material));
+    let res_id = project.resources().len() - 1;
+    if is_consumable {
+        project
+            .res_into_consumable(res_id)
+            .expect("A new material should be convertible to consumable. This is a bug.");
+    }
+
+    match project.resource_mut(res_id).unwrap() {
+        Resource::Material(material) => {
+            if let Some(quantity) = quantity {
+                material.update_quantity(quantity);
+            }
+            if let Some(cost) = cost {
+                material.update_cost_per_unit(cost);
+            }
+        }
+        _ => panic!("Resource {res_id} should be a material. This is a bug."),
+    }
+
+    res_id
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use super::FileError;
+
+/// Extension used for CSV files.
+pub const EXTENSION: &str = "csv";
+
+/// The content of a CSV file with a header row.
+#[derive(Debug, Clone, Default)]
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+pub fn read(path: &Path) -> Result<CsvTable, FileError> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_path(path)
+        .map_err(file_error)?;
+
+    let headers = reader
+        .headers()
+        .map_err(file_error)?
+        .iter()
+        .map(ToOwned::to_owned)
+        .collect();
+    let rows = reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(ToOwned::to_owned).collect())
+                .map_err(file_error)
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(CsvTable { headers, rows })
+}
+
+pub fn write(path: &Path, table: &CsvTable) -> Result<(), FileError> {
+    let mut writer = ::csv::Writer::from_path(path).map_err(file_error)?;
+    writer.write_record(&table.headers).map_err(file_error)?;
+    for row in &table.rows {
+        writer.write_record(row).map_err(file_error)?;
+    }
+    writer.flush().map_err(|e| FileError::Io(e.to_string()))
+}
+
+fn file_error(e: ::csv::Error) -> FileError {
+    if e.is_io_error() {
+        FileError::Io(e.to_string())
+    } else {
+        FileError::Malformed(e.to_string())
+    }
+}
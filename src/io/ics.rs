@@ -0,0 +1,220 @@
+//! iCalendar (`.ics`) files, read for the days they close.
+//!
+//! Every event is a closure: all-day events close the days they span, and
+//! timed events the days they touch. Yearly recurrences, which holiday
+//! calendars use for fixed-date holidays, are repeated; other recurrences
+//! only close their first occurrence, and are reported.
+
+use std::{collections::BTreeSet, fs, path::Path};
+
+use chrono::{Datelike, Months, NaiveDate, Utc};
+
+use super::FileError;
+
+/// Extension used for iCalendar files.
+pub const EXTENSION: &str = "ics";
+
+/// How many years from now yearly events without an end are repeated for.
+const RECURRENCE_YEARS: u32 = 10;
+
+/// Days off read from an iCalendar file, and the events that couldn't be
+/// fully imported.
+#[derive(Debug, Clone, Default)]
+pub struct DaysOff {
+    pub dates: BTreeSet<NaiveDate>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Event {
+    summary: String,
+    start: Option<String>,
+    end: Option<String>,
+    rule: Option<String>,
+    is_cancelled: bool,
+}
+
+pub fn read(path: &Path) -> Result<DaysOff, FileError> {
+    let content = fs::read_to_string(path).map_err(|e| FileError::Io(e.to_string()))?;
+    let horizon = Utc::now().date_naive() + Months::new(12 * RECURRENCE_YEARS);
+    parse(&content, horizon)
+}
+
+/// Reads the days closed by the events of `content`. Yearly events without
+/// an end are repeated up to `horizon`.
+fn parse(content: &str, horizon: NaiveDate) -> Result<DaysOff, FileError> {
+    // Long lines are folded: a line starting with a space or a tab continues
+    // the previous one.
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    if lines.first().map(|line| line.trim()) != Some("BEGIN:VCALENDAR") {
+        return Err(FileError::Malformed("not an iCalendar file".to_owned()));
+    }
+
+    let mut days_off = DaysOff::default();
+    // Components nested in events, like alarms, are skipped.
+    let mut components: Vec<String> = Vec::new();
+    let mut event = Event::default();
+    for line in &lines {
+        let Some((name_and_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name_and_params
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        let value = value.trim();
+        match name.as_str() {
+            "BEGIN" => {
+                if value.eq_ignore_ascii_case("VEVENT") {
+                    event = Event::default();
+                }
+                components.push(value.to_ascii_uppercase());
+            }
+            "END" => {
+                if components.pop().as_deref() == Some("VEVENT") {
+                    add_event(&std::mem::take(&mut event), horizon, &mut days_off);
+                }
+            }
+            _ if components.last().map(String::as_str) != Some("VEVENT") => {}
+            "SUMMARY" => event.summary = value.replace("\\,", ",").replace("\\;", ";"),
+            "DTSTART" => event.start = Some(value.to_owned()),
+            "DTEND" => event.end = Some(value.to_owned()),
+            "RRULE" => event.rule = Some(value.to_ascii_uppercase()),
+            "STATUS" => event.is_cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+
+    Ok(days_off)
+}
+
+fn add_event(event: &Event, horizon: NaiveDate, days_off: &mut DaysOff) {
+    if event.is_cancelled {
+        return;
+    }
+    let summary = &event.summary;
+    let Some((first, last)) = event_days(event) else {
+        days_off
+            .warnings
+            .push(format!("Event \"{summary}\": dates couldn't be read"));
+        return;
+    };
+
+    let mut occurrences = 1;
+    let mut until = horizon;
+    if let Some(rule) = &event.rule {
+        let parts = rule
+            .split(';')
+            .filter_map(|part| part.split_once('='))
+            .collect::<Vec<_>>();
+        let part = |name: &str| parts.iter().find(|(n, _)| *n == name).map(|(_, v)| *v);
+        match part("FREQ") {
+            Some("YEARLY") if part("INTERVAL").is_none_or(|interval| interval == "1") => {
+                if let Some(date) = part("UNTIL").and_then(parse_date) {
+                    until = until.min(date);
+                }
+                occurrences = part("COUNT")
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(i32::MAX);
+            }
+            frequency => days_off.warnings.push(format!(
+                "Event \"{summary}\": only the first occurrence of its {} recurrence was imported",
+                frequency.unwrap_or("unknown").to_ascii_lowercase()
+            )),
+        }
+    }
+
+    for year in 0..occurrences {
+        // Events on February 29th only happen on leap years.
+        let shift = |date: NaiveDate| date.with_year(date.year() + year);
+        let (Some(first), Some(last)) = (shift(first), shift(last)) else {
+            continue;
+        };
+        if year > 0 && first > until {
+            break;
+        }
+        days_off
+            .dates
+            .extend(first.iter_days().take_while(|day| *day <= last));
+    }
+}
+
+/// First and last day of an event. The end of all-day events, and of events
+/// ending at midnight, is exclusive.
+fn event_days(event: &Event) -> Option<(NaiveDate, NaiveDate)> {
+    let first = parse_date(event.start.as_deref()?)?;
+    let last = match event.end.as_deref() {
+        Some(end) => {
+            let is_exclusive = end.len() == 8 || end.get(9..15) == Some("000000");
+            let end = parse_date(end)?;
+            if is_exclusive { end.pred_opt()? } else { end }
+        }
+        None => first,
+    };
+
+    Some((first, last.max(first)))
+}
+
+/// Parses the date of an iCalendar date or date-time, like `20251225` or
+/// `20251225T090000Z`.
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::parse;
+
+    #[test]
+    fn parse_repeats_yearly_holidays() {
+        let content = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20231225\r
+DTEND;VALUE=DATE:20231227\r
+RRULE:FREQ=YEARLY\r
+SUMMARY:Christmas\\, Boxing \r
+ Day\r
+BEGIN:VALARM\r
+DTSTART:20231224T090000Z\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART:20240812T090000Z\r
+DTEND:20240816T170000Z\r
+SUMMARY:Shutdown\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20240101\r
+RRULE:FREQ=MONTHLY\r
+SUMMARY:Inventory\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let days_off = parse(content, date(2026, 1, 1)).unwrap();
+
+        let christmas = [2023, 2024, 2025]
+            .into_iter()
+            .flat_map(|y| [date(y, 12, 25), date(y, 12, 26)]);
+        let shutdown = (12..=16).map(|d| date(2024, 8, d));
+        let mut expected = christmas
+            .chain(shutdown)
+            .chain([date(2024, 1, 1)])
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(days_off.dates.into_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(days_off.warnings.len(), 1);
+        assert!(parse("BEGIN:VCARD", date(2026, 1, 1)).is_err());
+    }
+}
@@ -0,0 +1,1262 @@
+//! Microsoft Project XML (MSPDI) interchange.
+//!
+//! Only the subset of MSPDI that PlanTer can represent is imported. Anything
+//! else is listed in an [`ImportReport`] instead of being dropped silently.
+//! Exported documents contain only the elements PlanTer knows about, and MS
+//! Project fills in the rest with its defaults.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::Path,
+    str::FromStr,
+};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use planter_core::{
+    duration::PositiveDuration,
+    person::{EmailAddress, Person},
+    project::Project,
+    resources::{Material, Resource},
+    task::Task,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calendar::{Calendar, WEEKDAYS},
+    duration_format::{DurationFormat, DurationUnit},
+    extras::{Assignment, Ids, Progress, ProjectExtras},
+    scheduling::{self, Constraint, ConstraintKind, Link, LinkKind},
+};
+
+use super::{FileError, add_material};
+
+/// Extension used for MSPDI files.
+pub const EXTENSION: &str = "xml";
+
+/// Microsoft Project's XML namespace.
+const NAMESPACE: &str = "http://schemas.microsoft.com/project";
+
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+const TIME_FORMAT: &str = "%H:%M:%S";
+
+/// MSPDI resource types.
+const MATERIAL_RESOURCE: u8 = 0;
+const WORK_RESOURCE: u8 = 1;
+
+/// MSPDI link types.
+const FINISH_TO_FINISH: u8 = 0;
+const FINISH_TO_START: u8 = 1;
+const START_TO_FINISH: u8 = 2;
+const START_TO_START: u8 = 3;
+
+/// MSPDI constraint types. The others, such as "As Late As Possible", aren't
+/// supported.
+const AS_SOON_AS_POSSIBLE: u8 = 0;
+const MUST_START_ON: u8 = 2;
+const START_NO_EARLIER_THAN: u8 = 4;
+const FINISH_NO_LATER_THAN: u8 = 7;
+
+/// MSPDI duration formats, which lags use too. Each is followed by its
+/// elapsed time counterpart.
+const MINUTES_FORMAT: u8 = 3;
+const HOURS_FORMAT: u8 = 5;
+const DAYS_FORMAT: u8 = 7;
+const WEEKS_FORMAT: u8 = 9;
+
+/// MSPDI day type of calendar exceptions. Days of the week go from 1 for
+/// Sunday to 7 for Saturday.
+const EXCEPTION_DAY_TYPE: u8 = 0;
+
+/// UID of the calendar of the project. The calendars of people with days off
+/// follow it.
+const CALENDAR_UID: u32 = 1;
+
+/// MSPDI rate formats.
+const HOURLY_RATE_FORMAT: u8 = 2;
+/// Rates expressed per material unit, instead of per unit of time.
+const MATERIAL_RATE_FORMAT: u8 = 8;
+
+/// An MSPDI document, as read from or written to disk.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename = "Project", rename_all = "PascalCase")]
+pub struct MspdiDocument {
+    #[serde(rename = "@xmlns", skip_serializing_if = "Option::is_none")]
+    xmlns: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// The base calendar of the project.
+    #[serde(rename = "CalendarUID", skip_serializing_if = "Option::is_none")]
+    calendar_uid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minutes_per_day: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minutes_per_week: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_format: Option<u8>,
+    #[serde(default)]
+    calendars: Calendars,
+    #[serde(default)]
+    tasks: Tasks,
+    #[serde(default)]
+    resources: Resources,
+    #[serde(default)]
+    assignments: Assignments,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Calendars {
+    #[serde(rename = "Calendar", default)]
+    items: Vec<MspdiCalendar>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MspdiCalendar {
+    #[serde(rename = "UID")]
+    uid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_base_calendar: Option<u8>,
+    /// The calendar this one adds exceptions to, for resource calendars.
+    #[serde(rename = "BaseCalendarUID", skip_serializing_if = "Option::is_none")]
+    base_calendar_uid: Option<u32>,
+    #[serde(default)]
+    week_days: WeekDays,
+    /// Written by MS Project 2007 and later, in addition to exceptional
+    /// [`WeekDay`]s.
+    #[serde(default, skip_serializing)]
+    exceptions: Exceptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct WeekDays {
+    #[serde(rename = "WeekDay", default)]
+    items: Vec<WeekDay>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct WeekDay {
+    day_type: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day_working: Option<u8>,
+    /// The dates of an exception.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_period: Option<TimePeriod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    working_times: Option<WorkingTimes>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TimePeriod {
+    from_date: String,
+    to_date: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct WorkingTimes {
+    #[serde(rename = "WorkingTime", default)]
+    items: Vec<WorkingTime>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct WorkingTime {
+    from_time: String,
+    to_time: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Exceptions {
+    #[serde(rename = "Exception", default)]
+    items: Vec<Exception>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Exception {
+    time_period: Option<TimePeriod>,
+    #[serde(default)]
+    day_working: Option<u8>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Tasks {
+    #[serde(rename = "Task", default)]
+    items: Vec<MspdiTask>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MspdiTask {
+    #[serde(rename = "UID")]
+    uid: u32,
+    #[serde(rename = "ID", skip_serializing_if = "Option::is_none")]
+    id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_null: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outline_level: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    finish: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    work: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effort_driven: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    milestone: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percent_complete: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual_start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual_finish: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    constraint_type: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    constraint_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deadline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    #[serde(rename = "PredecessorLink", default)]
+    predecessor_links: Vec<PredecessorLink>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PredecessorLink {
+    #[serde(rename = "PredecessorUID")]
+    predecessor_uid: u32,
+    #[serde(rename = "Type", skip_serializing_if = "Option::is_none")]
+    link_type: Option<u8>,
+    /// In tenths of minutes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link_lag: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lag_format: Option<u8>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Resources {
+    #[serde(rename = "Resource", default)]
+    items: Vec<MspdiResource>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MspdiResource {
+    #[serde(rename = "UID")]
+    uid: u32,
+    #[serde(rename = "ID", skip_serializing_if = "Option::is_none")]
+    id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(rename = "Type", skip_serializing_if = "Option::is_none")]
+    resource_type: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_null: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    standard_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    standard_rate_format: Option<u8>,
+    #[serde(rename = "CalendarUID", skip_serializing_if = "Option::is_none")]
+    calendar_uid: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Assignments {
+    #[serde(rename = "Assignment", default)]
+    items: Vec<MspdiAssignment>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MspdiAssignment {
+    #[serde(rename = "UID", default)]
+    uid: u32,
+    #[serde(rename = "TaskUID")]
+    task_uid: u32,
+    #[serde(rename = "ResourceUID")]
+    resource_uid: u32,
+    /// 1 for full time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    units: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    work: Option<String>,
+}
+
+/// Everything that couldn't be mapped to a PlanTer project during an import.
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub warnings: Vec<String>,
+}
+
+impl ImportReport {
+    fn warn(&mut self, warning: impl ToString) {
+        self.warnings.push(warning.to_string());
+    }
+}
+
+pub fn read(path: &Path) -> Result<MspdiDocument, FileError> {
+    let content = fs::read_to_string(path).map_err(|e| FileError::Io(e.to_string()))?;
+    quick_xml::de::from_str(&content).map_err(|e| FileError::Malformed(e.to_string()))
+}
+
+pub fn write(path: &Path, document: &MspdiDocument) -> Result<(), FileError> {
+    let mut content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#.to_owned();
+    content.push('\n');
+    let mut serializer = quick_xml::se::Serializer::new(&mut content);
+    serializer.indent(' ', 2);
+    document
+        .serialize(serializer)
+        .map_err(|e| FileError::Malformed(e.to_string()))?;
+    fs::write(path, content).map_err(|e| FileError::Io(e.to_string()))
+}
+
+/// Builds a project out of an MSPDI document.
+pub fn import(document: MspdiDocument) -> (Project, ProjectExtras, ImportReport) {
+    let mut report = ImportReport::default();
+    let mut project = Project::new(document.name.as_deref().unwrap_or("Imported project"));
+    let mut extras = ProjectExtras::default();
+    extras.set_calendar(import_calendar(&document, &mut report));
+    extras.set_duration_format(import_duration_format(&document, &mut report));
+
+    // MS Project stores the project summary as the task with UID 0, and the
+    // "unassigned" resource as the resource with UID 0.
+    let tasks = document
+        .tasks
+        .items
+        .iter()
+        .filter(|t| t.uid != 0 && t.is_null != Some(1))
+        .collect::<Vec<_>>();
+    let task_indices = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (t.uid, i))
+        .collect::<HashMap<u32, usize>>();
+
+    // Tasks are listed in outline order, so the parent of a task is the last
+    // task above it with a lower outline level.
+    let mut ancestors: Vec<(u8, usize)> = Vec::new();
+    for (i, mspdi_task) in tasks.iter().enumerate() {
+        project.add_task(import_task(mspdi_task, extras.calendar(), &mut report));
+        extras.set_milestone(i, mspdi_task.milestone == Some(1));
+        extras.set_progress(i, import_progress(mspdi_task, &mut report));
+        extras.set_constraint(i, import_constraint(mspdi_task, &mut report));
+        extras.set_deadline(i, mspdi_task.deadline.as_deref().and_then(parse_date));
+
+        let level = mspdi_task.outline_level.unwrap_or(1);
+        while ancestors.last().is_some_and(|&(l, _)| l >= level) {
+            ancestors.pop();
+        }
+        extras
+            .set_parent(i, ancestors.last().map(|&(_, parent)| parent))
+            .expect("Tasks can only be nested under tasks above them. This is a bug.");
+        ancestors.push((level, i));
+    }
+    let task_uids = tasks.iter().map(|t| t.uid).collect::<Vec<_>>();
+    extras.set_task_ids(import_ids(&task_uids, "Tasks", &mut report));
+
+    for (i, mspdi_task) in tasks.iter().enumerate() {
+        let name = mspdi_task.name.as_deref().unwrap_or_default();
+        let mut predecessors = Vec::new();
+        let mut links = Vec::new();
+        for link in &mspdi_task.predecessor_links {
+            let Some(&predecessor) = task_indices.get(&link.predecessor_uid) else {
+                report.warn(format!(
+                    "Task \"{name}\": link to unknown task {} was dropped",
+                    link.predecessor_uid
+                ));
+                continue;
+            };
+            let kind = match link.link_type.unwrap_or(FINISH_TO_START) {
+                FINISH_TO_FINISH => LinkKind::FinishToFinish,
+                START_TO_FINISH => LinkKind::StartToFinish,
+                START_TO_START => LinkKind::StartToStart,
+                FINISH_TO_START => LinkKind::FinishToStart,
+                other => {
+                    report.warn(format!(
+                        "Task \"{name}\": link of unknown type {other} from task {} was imported as finish-to-start",
+                        link.predecessor_uid
+                    ));
+                    LinkKind::FinishToStart
+                }
+            };
+            predecessors.push(predecessor);
+            links.push(Link {
+                kind,
+                lag: TimeDelta::seconds(link.link_lag.unwrap_or(0) * 6),
+            });
+        }
+        if predecessors.is_empty() {
+            continue;
+        }
+        if project.update_predecessors(i, &predecessors).is_err() {
+            report.warn(format!(
+                "Task \"{name}\": predecessors couldn't be imported"
+            ));
+            continue;
+        }
+        for (predecessor, link) in predecessors.into_iter().zip(links) {
+            extras.set_link(predecessor, i, link);
+        }
+    }
+
+    let mut resource_indices = HashMap::new();
+    let mut resource_uids = Vec::new();
+    for resource in &document.resources.items {
+        if resource.uid == 0 || resource.is_null == Some(1) {
+            continue;
+        }
+        let name = resource.name.clone().unwrap_or_default();
+        let resource_count = project.resources().len();
+        import_resource(&mut project, resource, &name, &mut report);
+        if project.resources().len() > resource_count {
+            resource_indices.insert(resource.uid, resource_count);
+            resource_uids.push(resource.uid);
+            // Resource calendars only add days off to the project calendar.
+            if let Some(calendar) = document
+                .calendars
+                .items
+                .iter()
+                .find(|c| resource.calendar_uid == Some(c.uid) && c.is_base_calendar != Some(1))
+            {
+                extras.set_days_off(resource_count, import_days_off(calendar, &mut report));
+            }
+        }
+    }
+    extras.set_resource_ids(import_ids(&resource_uids, "Resources", &mut report));
+
+    // Assignments to the unassigned resource, or to resources that weren't
+    // imported, have already been reported through their resource.
+    let mut assignments = vec![Vec::new(); tasks.len()];
+    for assignment in &document.assignments.items {
+        if let (Some(&task), Some(&resource)) = (
+            task_indices.get(&assignment.task_uid),
+            resource_indices.get(&assignment.resource_uid),
+        ) {
+            assignments[task].push((resource, import_assignment(assignment, &mut report)));
+        }
+    }
+    for (task, assignments) in assignments.iter().enumerate() {
+        extras.set_assignments(task, assignments);
+        extras.set_effort_driven(task, tasks[task].effort_driven == Some(1));
+    }
+
+    (project, extras, report)
+}
+
+fn import_progress(mspdi_task: &MspdiTask, report: &mut ImportReport) -> Progress {
+    let name = mspdi_task.name.as_deref().unwrap_or_default();
+    let percent_complete = match mspdi_task.percent_complete.unwrap_or(0) {
+        p if p > 100 => {
+            report.warn(format!(
+                "Task \"{name}\": progress of {p}% was not imported"
+            ));
+            0
+        }
+        p => p,
+    };
+    let date = |date: &Option<String>| date.as_deref().and_then(parse_date);
+
+    // Tasks that made some progress have started, even if MS Project
+    // didn't record when.
+    Progress {
+        percent_complete,
+        actual_start: date(&mspdi_task.actual_start)
+            .or_else(|| date(&mspdi_task.start))
+            .filter(|_| percent_complete > 0),
+        actual_finish: date(&mspdi_task.actual_finish)
+            .or_else(|| date(&mspdi_task.finish))
+            .filter(|_| percent_complete == 100),
+    }
+}
+
+fn import_task(mspdi_task: &MspdiTask, calendar: &Calendar, report: &mut ImportReport) -> Task {
+    let name = mspdi_task.name.clone().unwrap_or_default();
+    let mut task = Task::new(name.clone());
+    if let Some(notes) = &mspdi_task.notes {
+        task.edit_description(notes.clone());
+    }
+
+    if mspdi_task.percent_complete == Some(100) {
+        task.toggle_completed();
+    }
+    let start = mspdi_task.start.as_deref().and_then(parse_date);
+    let finish = mspdi_task.finish.as_deref().and_then(parse_date);
+    if let Some(start) = start
+        && task.edit_start(start).is_err()
+    {
+        report.warn(format!("Task \"{name}\": start {start} was not imported"));
+    }
+    // Milestones have no duration, so their finish is their start.
+    if mspdi_task.milestone == Some(1) {
+        return task;
+    }
+    if let Some(finish) = finish {
+        if task.edit_finish(finish).is_err() {
+            report.warn(format!("Task \"{name}\": finish {finish} was not imported"));
+        }
+    } else if let Some(duration) = mspdi_task.duration.as_deref().and_then(parse_duration) {
+        // Durations are in working time.
+        if let Some(start) = task.start() {
+            if task.edit_finish(calendar.add(start, duration)).is_err() {
+                report.warn(format!("Task \"{name}\": duration was not imported"));
+            }
+        } else {
+            match PositiveDuration::parse_from_str(&format!("{} h", duration.num_hours())) {
+                Ok(duration) => task.edit_duration(duration),
+                Err(_) => report.warn(format!("Task \"{name}\": duration was not imported")),
+            }
+        }
+    }
+
+    task
+}
+
+/// Imports the base calendar of the project, or the first calendar if the
+/// project doesn't name one. PlanTer has the same working hours on every
+/// working day, so those of the first working day are used.
+fn import_calendar(document: &MspdiDocument, report: &mut ImportReport) -> Calendar {
+    let calendars = &document.calendars.items;
+    let Some(mspdi_calendar) = calendars
+        .iter()
+        .find(|c| Some(c.uid) == document.calendar_uid)
+        .or_else(|| calendars.iter().find(|c| c.is_base_calendar == Some(1)))
+        .or(calendars.first())
+    else {
+        return Calendar::default();
+    };
+
+    let mut calendar = Calendar::default();
+    let name = mspdi_calendar.name.as_deref().unwrap_or_default();
+    let mut working_days = Vec::new();
+    let mut working_hours: Option<Vec<(NaiveTime, NaiveTime)>> = None;
+    for week_day in &mspdi_calendar.week_days.items {
+        let is_working = week_day.day_working == Some(1);
+        if week_day.day_type == EXCEPTION_DAY_TYPE {
+            continue;
+        }
+        if week_day.day_type > 7 {
+            report.warn(format!(
+                "Calendar \"{name}\": unknown day type {} was ignored",
+                week_day.day_type
+            ));
+            continue;
+        }
+        let weekday = WEEKDAYS[(week_day.day_type as usize + 5) % 7];
+        working_days.push((weekday, is_working));
+        if !is_working {
+            continue;
+        }
+
+        let hours = week_day
+            .working_times
+            .iter()
+            .flat_map(|times| &times.items)
+            .map(|time| {
+                let parse = |s: &str| NaiveTime::parse_from_str(s, TIME_FORMAT).ok();
+                Some((parse(&time.from_time)?, parse(&time.to_time)?))
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(hours) = hours else {
+            report.warn(format!(
+                "Calendar \"{name}\": invalid working times on {weekday} were ignored"
+            ));
+            continue;
+        };
+        match &working_hours {
+            _ if hours.is_empty() => {}
+            None => working_hours = Some(hours),
+            Some(first) if *first != hours => report.warn(format!(
+                "Calendar \"{name}\": working times on {weekday} differ from the other days and were ignored"
+            )),
+            Some(_) => {}
+        }
+    }
+
+    // Days are made working first, so that some day is always worked.
+    working_days.sort_by_key(|&(_, is_working)| !is_working);
+    for (weekday, is_working) in working_days {
+        if calendar.set_works_on(weekday, is_working).is_err() {
+            report.warn(format!(
+                "Calendar \"{name}\": no day of the week is worked, {weekday} was kept"
+            ));
+        }
+    }
+    if let Some(hours) = working_hours
+        && calendar.set_working_hours(hours).is_err()
+    {
+        report.warn(format!(
+            "Calendar \"{name}\": overlapping working times were ignored"
+        ));
+    }
+
+    for day in import_days_off(mspdi_calendar, report) {
+        calendar.add_holiday(day);
+    }
+
+    calendar
+}
+
+/// Reads the unit durations are shown in, and the length of days and weeks.
+fn import_duration_format(document: &MspdiDocument, report: &mut ImportReport) -> DurationFormat {
+    let mut duration_format = DurationFormat::default();
+    if let Some(format) = document.duration_format {
+        // Elapsed time is shown in the same unit as working time.
+        match format {
+            MINUTES_FORMAT | 4 => duration_format.unit = DurationUnit::Minutes,
+            HOURS_FORMAT | 6 => duration_format.unit = DurationUnit::Hours,
+            DAYS_FORMAT | 8 => duration_format.unit = DurationUnit::Days,
+            WEEKS_FORMAT | 10 => duration_format.unit = DurationUnit::Weeks,
+            _ => report.warn(format!(
+                "Duration format {format} is not supported, durations are shown in hours"
+            )),
+        }
+    }
+    if let Some(minutes_per_day) = document.minutes_per_day
+        && duration_format
+            .set_hours_per_day(TimeDelta::minutes(minutes_per_day))
+            .is_err()
+    {
+        report.warn(format!(
+            "{minutes_per_day} minutes per day is not a valid day length, 8 hours are used"
+        ));
+    }
+    if let Some(minutes_per_week) = document.minutes_per_week {
+        let minutes_per_day = duration_format.hours_per_day().num_minutes();
+        let is_valid = minutes_per_week % minutes_per_day == 0
+            && u8::try_from(minutes_per_week / minutes_per_day)
+                .is_ok_and(|days| duration_format.set_days_per_week(days).is_ok());
+        if !is_valid {
+            report.warn(format!(
+                "{minutes_per_week} minutes per week is not a whole number of days, 5 days are used"
+            ));
+        }
+    }
+
+    duration_format
+}
+
+fn import_constraint(mspdi_task: &MspdiTask, report: &mut ImportReport) -> Option<Constraint> {
+    let name = mspdi_task.name.as_deref().unwrap_or_default();
+    let kind = match mspdi_task.constraint_type? {
+        AS_SOON_AS_POSSIBLE => return None,
+        MUST_START_ON => ConstraintKind::MustStartOn,
+        START_NO_EARLIER_THAN => ConstraintKind::StartNoEarlierThan,
+        FINISH_NO_LATER_THAN => ConstraintKind::FinishNoLaterThan,
+        other => {
+            report.warn(format!(
+                "Task \"{name}\": constraint of unsupported type {other} was dropped"
+            ));
+            return None;
+        }
+    };
+    let Some(date) = mspdi_task.constraint_date.as_deref().and_then(parse_date) else {
+        report.warn(format!(
+            "Task \"{name}\": {kind} constraint without a valid date was dropped"
+        ));
+        return None;
+    };
+
+    Some(Constraint { kind, date })
+}
+
+/// IDs of tasks or resources, which are their UIDs minus one. If a UID is
+/// given twice, they're numbered in order instead.
+fn import_ids(uids: &[u32], items: &str, report: &mut ImportReport) -> Ids {
+    let ids = uids.iter().map(|&uid| uid as usize - 1).collect::<Vec<_>>();
+    let next = ids.iter().max().map_or(0, |&id| id + 1);
+    Ids::new(ids, next).unwrap_or_else(|_| {
+        report.warn(format!("{items} with the same UID were numbered in order"));
+        Ids::default()
+    })
+}
+
+/// Days closed by the non-working exceptions of a calendar, whether they're
+/// exceptional [`WeekDay`]s or [`Exception`]s.
+fn import_days_off(
+    mspdi_calendar: &MspdiCalendar,
+    report: &mut ImportReport,
+) -> BTreeSet<NaiveDate> {
+    let name = mspdi_calendar.name.as_deref().unwrap_or_default();
+    let exceptions = mspdi_calendar
+        .week_days
+        .items
+        .iter()
+        .filter(|week_day| week_day.day_type == EXCEPTION_DAY_TYPE)
+        .map(|week_day| {
+            (
+                week_day.time_period.as_ref(),
+                week_day.day_working == Some(1),
+            )
+        })
+        .chain(
+            mspdi_calendar
+                .exceptions
+                .items
+                .iter()
+                .map(|e| (e.time_period.as_ref(), e.day_working == Some(1))),
+        );
+
+    let mut days_off = BTreeSet::new();
+    for (time_period, is_working) in exceptions {
+        if is_working {
+            report.warn(format!(
+                "Calendar \"{name}\": working exceptions aren't supported"
+            ));
+            continue;
+        }
+        let date = |s: &str| parse_date(s).map(|date| date.date_naive());
+        let Some((from, to)) =
+            time_period.and_then(|period| Some((date(&period.from_date)?, date(&period.to_date)?)))
+        else {
+            report.warn(format!(
+                "Calendar \"{name}\": an exception without valid dates was ignored"
+            ));
+            continue;
+        };
+        days_off.extend(from.iter_days().take_while(|day| *day <= to));
+    }
+
+    days_off
+}
+
+fn import_assignment(assignment: &MspdiAssignment, report: &mut ImportReport) -> Assignment {
+    let mut imported = Assignment::default();
+    match assignment.units.map(|units| (units * 100.).round()) {
+        Some(units) if (1. ..=u16::MAX as f64).contains(&units) => imported.units = units as u16,
+        Some(_) => report.warn(format!(
+            "Assignment {}: units are out of range, full time was assumed",
+            assignment.uid
+        )),
+        None => {}
+    }
+    if let Some(work) = &assignment.work {
+        match parse_duration(work) {
+            Some(work) => imported.work = work,
+            None => report.warn(format!(
+                "Assignment {}: work {work} was not imported",
+                assignment.uid
+            )),
+        }
+    }
+
+    imported
+}
+
+fn import_resource(
+    project: &mut Project,
+    resource: &MspdiResource,
+    name: &str,
+    report: &mut ImportReport,
+) {
+    let rate = resource.standard_rate.filter(|rate| *rate > 0.);
+    let cents = rate.and_then(to_cents);
+    if rate.is_some() && cents.is_none() {
+        report.warn(format!("Resource \"{name}\": rate is out of range"));
+    }
+
+    match resource.resource_type.unwrap_or(WORK_RESOURCE) {
+        WORK_RESOURCE => {
+            let Some(mut person) = name
+                .trim()
+                .rsplit_once(' ')
+                .and_then(|(first_name, last_name)| Person::new(first_name.trim(), last_name))
+            else {
+                report.warn(format!(
+                    "Resource \"{name}\": couldn't be split into first and last name"
+                ));
+                return;
+            };
+            if let Some(email) = &resource.email_address {
+                match EmailAddress::from_str(email) {
+                    Ok(email) => person.update_email(email),
+                    Err(_) => report.warn(format!("Resource \"{name}\": invalid email {email}")),
+                }
+            }
+            project.add_resource(Resource::Personnel {
+                person,
+                hourly_rate: cents,
+            });
+        }
+        MATERIAL_RESOURCE => {
+            // Materials priced per unit are consumed, those priced per unit of time aren't.
+            let is_consumable = resource
+                .standard_rate_format
+                .is_none_or(|format| format == MATERIAL_RATE_FORMAT);
+            add_material(project, name, is_consumable, None, cents);
+        }
+        _ => report.warn(format!(
+            "Resource \"{name}\": cost resources aren't supported"
+        )),
+    }
+}
+
+/// Builds an MSPDI document out of a project.
+///
+/// UIDs are the IDs of tasks and resources plus one, and the 1-based position
+/// of assignments, since MS Project reserves UID 0 for the project summary
+/// task and the unassigned resource.
+/// Tasks are written in outline order, which MS Project relies on to nest
+/// them.
+pub fn export(project: &Project, extras: &ProjectExtras) -> MspdiDocument {
+    // MSPDI dates have no time zone: they're the wall-clock times of the
+    // project, which imports read back in UTC.
+    let format_date = |date: DateTime<Utc>| {
+        extras
+            .calendar()
+            .to_local(date)
+            .format(DATE_FORMAT)
+            .to_string()
+    };
+    let task_count = project.tasks().count();
+    let tasks = extras
+        .outline(task_count)
+        .into_iter()
+        .enumerate()
+        .map(|(position, entry)| {
+            let i = entry.task;
+            let task = project.task(i).unwrap();
+            MspdiTask {
+                uid: extras.task_ids().id(i) as u32 + 1,
+                id: Some(position as u32 + 1),
+                name: Some(task.name().to_owned()),
+                outline_level: Some(entry.depth as u8 + 1),
+                summary: Some(extras.is_summary(i, task_count) as u8),
+                milestone: Some(extras.is_milestone(i) as u8),
+                start: task.start().map(format_date),
+                finish: if extras.is_milestone(i) {
+                    task.start()
+                } else {
+                    task.finish()
+                }
+                .map(format_date),
+                duration: scheduling::task_duration(project, extras, i).map(format_duration),
+                work: Some(format_duration(extras.work(i))),
+                effort_driven: Some(extras.is_effort_driven(i) as u8),
+                percent_complete: Some(extras.progress(i).percent_complete),
+                actual_start: extras.progress(i).actual_start.map(format_date),
+                actual_finish: extras.progress(i).actual_finish.map(format_date),
+                constraint_type: Some(match extras.constraint(i).map(|c| c.kind) {
+                    None | Some(ConstraintKind::AsSoonAsPossible) => AS_SOON_AS_POSSIBLE,
+                    Some(ConstraintKind::MustStartOn) => MUST_START_ON,
+                    Some(ConstraintKind::StartNoEarlierThan) => START_NO_EARLIER_THAN,
+                    Some(ConstraintKind::FinishNoLaterThan) => FINISH_NO_LATER_THAN,
+                }),
+                constraint_date: extras.constraint(i).map(|c| format_date(c.date)),
+                deadline: extras.deadline(i).map(format_date),
+                notes: Some(task.description().to_owned()).filter(|d| !d.is_empty()),
+                predecessor_links: project
+                    .predecessors_indices(i)
+                    .map(|p| {
+                        let link = extras.link(p, i);
+                        PredecessorLink {
+                            predecessor_uid: extras.task_ids().id(p) as u32 + 1,
+                            link_type: Some(match link.kind {
+                                LinkKind::FinishToStart => FINISH_TO_START,
+                                LinkKind::StartToStart => START_TO_START,
+                                LinkKind::FinishToFinish => FINISH_TO_FINISH,
+                                LinkKind::StartToFinish => START_TO_FINISH,
+                            }),
+                            link_lag: Some(link.lag.num_seconds() / 6),
+                            lag_format: Some(HOURS_FORMAT),
+                        }
+                    })
+                    .collect(),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let mut calendars = vec![export_calendar(extras.calendar())];
+    let resources = project
+        .resources()
+        .iter()
+        .enumerate()
+        .map(|(res_id, resource)| {
+            let uid = extras.resource_ids().id(res_id) as u32 + 1;
+            let id = Some(res_id as u32 + 1);
+            match resource {
+                Resource::Personnel {
+                    person,
+                    hourly_rate,
+                } => {
+                    let name = format!("{} {}", person.first_name(), person.last_name());
+                    let days_off = extras.days_off(res_id).collect::<Vec<_>>();
+                    let calendar_uid = (!days_off.is_empty()).then(|| {
+                        let calendar_uid = CALENDAR_UID + calendars.len() as u32;
+                        calendars.push(MspdiCalendar {
+                            uid: calendar_uid,
+                            name: Some(name.clone()),
+                            is_base_calendar: Some(0),
+                            base_calendar_uid: Some(CALENDAR_UID),
+                            week_days: WeekDays {
+                                items: days_off.into_iter().map(export_day_off).collect(),
+                            },
+                            ..Default::default()
+                        });
+                        calendar_uid
+                    });
+                    MspdiResource {
+                        uid,
+                        id,
+                        name: Some(name),
+                        resource_type: Some(WORK_RESOURCE),
+                        email_address: person.email().map(|e| e.to_string()),
+                        standard_rate: hourly_rate.map(from_cents),
+                        standard_rate_format: Some(HOURLY_RATE_FORMAT),
+                        calendar_uid,
+                        ..Default::default()
+                    }
+                }
+                Resource::Material(material) => MspdiResource {
+                    uid,
+                    id,
+                    name: Some(material.name().to_owned()),
+                    resource_type: Some(MATERIAL_RESOURCE),
+                    standard_rate: material.cost_per_unit().map(from_cents),
+                    standard_rate_format: Some(match material {
+                        Material::Consumable(_) => MATERIAL_RATE_FORMAT,
+                        Material::NonConsumable(_) => HOURLY_RATE_FORMAT,
+                    }),
+                    ..Default::default()
+                },
+            }
+        })
+        .collect();
+
+    let assignments = (0..task_count)
+        .flat_map(|i| extras.assignments(i).map(move |assignment| (i, assignment)))
+        .enumerate()
+        .map(|(position, (i, (res_id, assignment)))| MspdiAssignment {
+            uid: position as u32 + 1,
+            task_uid: extras.task_ids().id(i) as u32 + 1,
+            resource_uid: extras.resource_ids().id(res_id) as u32 + 1,
+            units: Some(assignment.units as f64 / 100.),
+            work: Some(format_duration(assignment.work)),
+        })
+        .collect();
+
+    let duration_format = extras.duration_format();
+    MspdiDocument {
+        xmlns: Some(NAMESPACE.to_owned()),
+        name: Some(project.name().to_owned()),
+        calendar_uid: Some(CALENDAR_UID),
+        minutes_per_day: Some(duration_format.hours_per_day().num_minutes()),
+        minutes_per_week: Some(
+            duration_format.hours_per_day().num_minutes() * duration_format.days_per_week() as i64,
+        ),
+        duration_format: Some(match duration_format.unit {
+            DurationUnit::Minutes => MINUTES_FORMAT,
+            DurationUnit::Hours => HOURS_FORMAT,
+            DurationUnit::Days => DAYS_FORMAT,
+            DurationUnit::Weeks => WEEKS_FORMAT,
+        }),
+        calendars: Calendars { items: calendars },
+        tasks: Tasks { items: tasks },
+        resources: Resources { items: resources },
+        assignments: Assignments { items: assignments },
+    }
+}
+
+/// Exports the calendar of the project as a base calendar, with holidays as
+/// exceptional days.
+fn export_calendar(calendar: &Calendar) -> MspdiCalendar {
+    let working_times = WorkingTimes {
+        items: calendar
+            .working_hours()
+            .iter()
+            .map(|(from, to)| WorkingTime {
+                from_time: from.format(TIME_FORMAT).to_string(),
+                to_time: to.format(TIME_FORMAT).to_string(),
+            })
+            .collect(),
+    };
+    let week_days = (1..=7).map(|day_type| {
+        let is_working = calendar.works_on(WEEKDAYS[(day_type as usize + 5) % 7]);
+        WeekDay {
+            day_type,
+            day_working: Some(is_working as u8),
+            working_times: is_working.then(|| working_times.clone()),
+            ..Default::default()
+        }
+    });
+    let holidays = calendar.holidays().map(export_day_off);
+
+    MspdiCalendar {
+        uid: CALENDAR_UID,
+        name: Some("Standard".to_owned()),
+        is_base_calendar: Some(1),
+        week_days: WeekDays {
+            items: week_days.chain(holidays).collect(),
+        },
+        ..Default::default()
+    }
+}
+
+/// A non-working exception for a whole day.
+fn export_day_off(date: NaiveDate) -> WeekDay {
+    WeekDay {
+        day_type: EXCEPTION_DAY_TYPE,
+        day_working: Some(0),
+        time_period: Some(TimePeriod {
+            from_date: date
+                .and_time(NaiveTime::MIN)
+                .format(DATE_FORMAT)
+                .to_string(),
+            to_date: date
+                .and_hms_opt(23, 59, 0)
+                .expect("Hardcoded times should be valid. This is a bug.")
+                .format(DATE_FORMAT)
+                .to_string(),
+        }),
+        ..Default::default()
+    }
+}
+
+fn from_cents(cents: u16) -> f64 {
+    cents as f64 / 100.
+}
+
+fn to_cents(amount: f64) -> Option<u16> {
+    let cents = (amount * 100.).round();
+    (0. ..=u16::MAX as f64)
+        .contains(&cents)
+        .then_some(cents as u16)
+}
+
+fn parse_date(s: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(s, DATE_FORMAT)
+        .ok()
+        .map(|date| date.and_utc())
+}
+
+/// Formats a duration the way MS Project writes it, e.g. `PT7H30M0S`.
+fn format_duration(duration: TimeDelta) -> String {
+    format!(
+        "PT{}H{}M0S",
+        duration.num_hours(),
+        duration.num_minutes() % 60
+    )
+}
+
+/// Parses an ISO 8601 duration as written by MS Project, e.g. `PT8H0M0S`.
+fn parse_duration(s: &str) -> Option<TimeDelta> {
+    let s = s.strip_prefix('P')?;
+    let (days, time) = match s.split_once('T') {
+        Some((days, time)) => (days, time),
+        None => (s, ""),
+    };
+
+    let mut duration = TimeDelta::zero();
+    if !days.is_empty() {
+        duration += TimeDelta::days(days.strip_suffix('D')?.parse().ok()?);
+    }
+
+    let mut number = String::new();
+    for c in time.chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'H' | 'M' | 'S' => {
+                let value = number.parse::<f64>().ok()?;
+                let seconds = match c {
+                    'H' => value * 3600.,
+                    'M' => value * 60.,
+                    _ => value,
+                };
+                duration += TimeDelta::milliseconds((seconds * 1000.) as i64);
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+
+    number.is_empty().then_some(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeDelta, Weekday};
+
+    use planter_core::{
+        person::Person,
+        project::Project,
+        resources::{Material, NonConsumable, Resource},
+        task::Task,
+    };
+
+    use super::{export, import, parse_duration};
+    use crate::{
+        calendar::Calendar,
+        duration_format::{DurationFormat, DurationUnit},
+        extras::{Assignment, ProjectExtras},
+        scheduling::{Constraint, ConstraintKind, Link, LinkKind},
+    };
+
+    #[test]
+    fn parse_duration_works() {
+        assert_eq!(parse_duration("PT8H0M0S"), Some(TimeDelta::hours(8)));
+        assert_eq!(parse_duration("PT1H30M0S"), Some(TimeDelta::minutes(90)));
+        assert_eq!(parse_duration("P2DT4H"), Some(TimeDelta::hours(52)));
+        assert_eq!(parse_duration("8h"), None);
+    }
+
+    #[test]
+    fn import_reports_unmapped_entries() {
+        let xml = r#"
+            <Project xmlns="http://schemas.microsoft.com/project">
+                <Name>Bridge</Name>
+                <Tasks>
+                    <Task><UID>0</UID><Name>Bridge</Name><Summary>1</Summary></Task>
+                    <Task>
+                        <UID>1</UID>
+                        <Name>Foundations</Name>
+                        <Start>2024-03-04T08:00:00</Start>
+                        <PercentComplete>60</PercentComplete>
+                    </Task>
+                    <Task>
+                        <UID>2</UID>
+                        <Name>Deck</Name>
+                        <PredecessorLink>
+                            <PredecessorUID>1</PredecessorUID>
+                            <Type>3</Type>
+                            <LinkLag>4800</LinkLag>
+                        </PredecessorLink>
+                    </Task>
+                </Tasks>
+                <Resources>
+                    <Resource><UID>1</UID><Name>Ada Lovelace</Name><Type>1</Type></Resource>
+                    <Resource><UID>2</UID><Name>Concrete</Name><Type>0</Type></Resource>
+                    <Resource><UID>3</UID><Name>Permits</Name><Type>2</Type></Resource>
+                </Resources>
+                <Assignments>
+                    <Assignment><UID>1</UID><TaskUID>2</TaskUID><ResourceUID>1</ResourceUID><Units>0.5</Units><Work>PT16H0M0S</Work></Assignment>
+                    <Assignment><UID>2</UID><TaskUID>2</TaskUID><ResourceUID>3</ResourceUID></Assignment>
+                </Assignments>
+            </Project>"#;
+
+        let (project, extras, report) = import(quick_xml::de::from_str(xml).unwrap());
+
+        assert_eq!(project.tasks().count(), 2);
+        assert_eq!(project.predecessors_indices(1).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(
+            extras.link(0, 1),
+            Link {
+                kind: LinkKind::StartToStart,
+                lag: TimeDelta::hours(8),
+            }
+        );
+        assert_eq!(extras.progress(0).percent_complete, 60);
+        assert!(extras.progress(0).actual_start.is_some());
+        assert_eq!(project.resources().len(), 2);
+        assert_eq!(
+            extras.assignments(1).collect::<Vec<_>>(),
+            vec![(
+                0,
+                Assignment {
+                    units: 50,
+                    work: TimeDelta::hours(16),
+                }
+            )]
+        );
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn export_can_be_imported_back() {
+        let mut project = Project::new("Bridge");
+        project.add_task(Task::new("Foundations".to_owned()));
+        project.add_task(Task::new("Deck".to_owned()));
+        project.update_predecessors(1, &[0]).unwrap();
+        let mut extras = ProjectExtras::default();
+        let link = Link {
+            kind: LinkKind::FinishToFinish,
+            lag: TimeDelta::minutes(-90),
+        };
+        extras.set_link(0, 1, link);
+        project.add_task(Task::new("Bridge".to_owned()));
+        extras.set_parent(0, Some(2)).unwrap();
+        extras.set_parent(1, Some(2)).unwrap();
+        project.add_resource(Resource::Material(Material::NonConsumable(
+            NonConsumable::new("Concrete"),
+        )));
+        let assignment = Assignment {
+            units: 150,
+            work: TimeDelta::minutes(450),
+        };
+        extras.set_assignments(0, &[(0, assignment)]);
+        extras.set_milestone(1, true);
+        let constraint = Constraint {
+            kind: ConstraintKind::FinishNoLaterThan,
+            date: NaiveDate::from_ymd_opt(2025, 6, 30)
+                .unwrap()
+                .and_hms_opt(17, 0, 0)
+                .unwrap()
+                .and_utc(),
+        };
+        extras.set_constraint(1, Some(constraint));
+        extras.set_deadline(0, Some(constraint.date));
+        let mut calendar = Calendar::default();
+        calendar.set_works_on(Weekday::Sat, true).unwrap();
+        calendar.add_holiday(NaiveDate::from_ymd_opt(2025, 12, 25).unwrap());
+        extras.set_calendar(calendar.clone());
+        project.add_resource(Resource::Personnel {
+            person: Person::new("Ada", "Lovelace").unwrap(),
+            hourly_rate: None,
+        });
+        let day_off = NaiveDate::from_ymd_opt(2025, 8, 15).unwrap();
+        extras.set_days_off(1, [day_off].into());
+        let mut duration_format = DurationFormat {
+            unit: DurationUnit::Weeks,
+            ..Default::default()
+        };
+        duration_format.set_days_per_week(4).unwrap();
+        extras.set_duration_format(duration_format);
+
+        let xml = quick_xml::se::to_string(&export(&project, &extras)).unwrap();
+        let (imported, imported_extras, report) = import(quick_xml::de::from_str(&xml).unwrap());
+
+        assert_eq!(imported.name(), "Bridge");
+        // The summary task comes first in outline order.
+        assert_eq!(imported.tasks().count(), 3);
+        assert_eq!(imported.task(0).unwrap().name(), "Bridge");
+        assert_eq!(imported_extras.task_ids().to_vec(3), (vec![2, 0, 1], 3));
+        assert_eq!(imported_extras.parent(2), Some(0));
+        assert_eq!(
+            imported.predecessors_indices(2).collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(imported_extras.link(1, 2), link);
+        assert!(imported_extras.is_milestone(2));
+        assert_eq!(imported_extras.constraint(2), Some(constraint));
+        assert_eq!(imported_extras.deadline(1), Some(constraint.date));
+        assert_eq!(
+            imported_extras.assignments(1).collect::<Vec<_>>(),
+            vec![(0, assignment)]
+        );
+        assert_eq!(imported_extras.calendar(), &calendar);
+        assert_eq!(
+            imported_extras.days_off(1).collect::<Vec<_>>(),
+            vec![day_off]
+        );
+        assert_eq!(imported_extras.duration_format(), &duration_format);
+        assert!(report.warnings.is_empty());
+    }
+}
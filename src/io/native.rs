@@ -0,0 +1,640 @@
+//! Native PlanTer project files.
+//!
+//! A project file is a JSON document tagged with the version of the format
+//! that wrote it, so that older files can still be recognised and migrated
+//! when the format changes.
+
+use std::{fs, path::Path, str::FromStr};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc, Weekday};
+use chrono_tz::Tz;
+use planter_core::{
+    duration::PositiveDuration,
+    person::{EmailAddress, Person, PhoneNumber},
+    project::Project,
+    resources::{Material, Resource},
+    task::Task,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calendar::{Calendar, WEEKDAYS},
+    duration_format::{DurationFormat, DurationUnit},
+    extras::{Assignment, Ids, Progress, ProjectExtras},
+    scheduling::{Constraint, ConstraintKind, Link, LinkKind},
+};
+
+use super::{FileError, add_material};
+
+/// Extension used for PlanTer project files.
+pub const EXTENSION: &str = "planter";
+/// Version of the format written by this release.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// On-disk representation of a [`Project`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectFile {
+    version: u32,
+    name: String,
+    tasks: Vec<TaskRecord>,
+    resources: Vec<ResourceRecord>,
+    calendar: CalendarRecord,
+    duration_format: DurationFormatRecord,
+    ids: IdsRecord,
+}
+
+/// Stable IDs of the tasks and resources, in order, and the next ones to
+/// give, so that IDs of removed ones aren't given again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IdsRecord {
+    tasks: Vec<usize>,
+    next_task: usize,
+    resources: Vec<usize>,
+    next_resource: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CalendarRecord {
+    working_days: Vec<Weekday>,
+    working_hours: Vec<(NaiveTime, NaiveTime)>,
+    holidays: Vec<NaiveDate>,
+    time_zone: Tz,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DurationFormatRecord {
+    unit: DurationUnit,
+    minutes_per_day: i64,
+    days_per_week: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct TaskRecord {
+    name: String,
+    description: String,
+    completed: bool,
+    start: Option<DateTime<Utc>>,
+    finish: Option<DateTime<Utc>>,
+    /// Duration of tasks without a finish.
+    duration_minutes: Option<i64>,
+    predecessors: Vec<usize>,
+    /// Links from predecessors that aren't finish-to-start without lag.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    links: Vec<LinkRecord>,
+    /// Index of the summary task this task is nested under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    assignments: Vec<AssignmentRecord>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    effort_driven: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    milestone: bool,
+    /// Duration the task gets back when it stops being a milestone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    milestone_duration_minutes: Option<i64>,
+    percent_complete: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actual_start: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actual_finish: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    constraint: Option<ConstraintRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deadline: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ConstraintRecord {
+    kind: ConstraintKind,
+    date: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct AssignmentRecord {
+    resource: usize,
+    units: u16,
+    work_minutes: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LinkRecord {
+    predecessor: usize,
+    kind: LinkKind,
+    lag_minutes: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ResourceRecord {
+    Personnel {
+        first_name: String,
+        last_name: String,
+        email: Option<String>,
+        phone: Option<String>,
+        hourly_rate: Option<u16>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        days_off: Vec<NaiveDate>,
+    },
+    Consumable {
+        name: String,
+        quantity: Option<u16>,
+        cost_per_unit: Option<u16>,
+    },
+    NonConsumable {
+        name: String,
+        quantity: Option<u16>,
+        hourly_rate: Option<u16>,
+    },
+}
+
+impl ProjectFile {
+    pub fn from_project(project: &Project, extras: &ProjectExtras) -> Self {
+        let tasks = project
+            .tasks()
+            .enumerate()
+            .map(|(i, task)| TaskRecord {
+                name: task.name().to_owned(),
+                description: task.description().to_owned(),
+                completed: task.completed(),
+                start: task.start(),
+                finish: task.finish(),
+                duration_minutes: extras
+                    .unscheduled_duration(i)
+                    .or_else(|| task.duration().map(|d| TimeDelta::hours(d.num_hours())))
+                    .map(|d| d.num_minutes()),
+                predecessors: project.predecessors_indices(i).collect(),
+                links: project
+                    .predecessors_indices(i)
+                    .map(|p| (p, extras.link(p, i)))
+                    .filter(|(_, link)| *link != Link::default())
+                    .map(|(predecessor, link)| LinkRecord {
+                        predecessor,
+                        kind: link.kind,
+                        lag_minutes: link.lag.num_minutes(),
+                    })
+                    .collect(),
+                parent: extras.parent(i),
+                assignments: extras
+                    .assignments(i)
+                    .map(|(resource, assignment)| AssignmentRecord {
+                        resource,
+                        units: assignment.units,
+                        work_minutes: assignment.work.num_minutes(),
+                    })
+                    .collect(),
+                effort_driven: extras.is_effort_driven(i),
+                milestone: extras.is_milestone(i),
+                milestone_duration_minutes: extras.milestone_duration(i).map(|d| d.num_minutes()),
+                percent_complete: extras.progress(i).percent_complete,
+                actual_start: extras.progress(i).actual_start,
+                actual_finish: extras.progress(i).actual_finish,
+                constraint: extras.constraint(i).map(|c| ConstraintRecord {
+                    kind: c.kind,
+                    date: c.date,
+                }),
+                deadline: extras.deadline(i),
+            })
+            .collect();
+
+        let resources = project
+            .resources()
+            .iter()
+            .enumerate()
+            .map(|(res_id, resource)| match resource {
+                Resource::Personnel {
+                    person,
+                    hourly_rate,
+                } => ResourceRecord::Personnel {
+                    first_name: person.first_name().to_owned(),
+                    last_name: person.last_name().to_owned(),
+                    email: person.email().map(|e| e.to_string()),
+                    phone: person.phone().map(|p| p.to_string()),
+                    hourly_rate: *hourly_rate,
+                    days_off: extras.days_off(res_id).collect(),
+                },
+                Resource::Material(material @ Material::Consumable(_)) => {
+                    ResourceRecord::Consumable {
+                        name: material.name().to_owned(),
+                        quantity: material.quantity(),
+                        cost_per_unit: material.cost_per_unit(),
+                    }
+                }
+                Resource::Material(material @ Material::NonConsumable(_)) => {
+                    ResourceRecord::NonConsumable {
+                        name: material.name().to_owned(),
+                        quantity: material.quantity(),
+                        hourly_rate: material.cost_per_unit(),
+                    }
+                }
+            })
+            .collect();
+
+        let calendar = extras.calendar();
+        let calendar = CalendarRecord {
+            working_days: WEEKDAYS
+                .into_iter()
+                .filter(|&weekday| calendar.works_on(weekday))
+                .collect(),
+            working_hours: calendar.working_hours().to_vec(),
+            holidays: calendar.holidays().collect(),
+            time_zone: calendar.time_zone(),
+        };
+        let duration_format = extras.duration_format();
+        let duration_format = DurationFormatRecord {
+            unit: duration_format.unit,
+            minutes_per_day: duration_format.hours_per_day().num_minutes(),
+            days_per_week: duration_format.days_per_week(),
+        };
+        let (task_ids, next_task) = extras.task_ids().to_vec(project.tasks().count());
+        let (resource_ids, next_resource) = extras.resource_ids().to_vec(project.resources().len());
+        let ids = IdsRecord {
+            tasks: task_ids,
+            next_task,
+            resources: resource_ids,
+            next_resource,
+        };
+
+        ProjectFile {
+            version: FORMAT_VERSION,
+            name: project.name().to_owned(),
+            tasks,
+            resources,
+            calendar,
+            duration_format,
+            ids,
+        }
+    }
+
+    pub fn into_project(self) -> Result<(Project, ProjectExtras), FileError> {
+        let mut project = Project::new(&self.name);
+        let mut extras = ProjectExtras::default();
+        extras.set_calendar(self.calendar.to_calendar()?);
+        extras.set_duration_format(self.duration_format.to_duration_format()?);
+        let ids = &self.ids;
+        let invalid = |items| FileError::Invalid(format!("the IDs of the {items} are invalid"));
+        if ids.tasks.len() != self.tasks.len() {
+            return Err(invalid("tasks"));
+        }
+        if ids.resources.len() != self.resources.len() {
+            return Err(invalid("resources"));
+        }
+        extras.set_task_ids(
+            Ids::new(ids.tasks.clone(), ids.next_task).map_err(|_| invalid("tasks"))?,
+        );
+        extras.set_resource_ids(
+            Ids::new(ids.resources.clone(), ids.next_resource).map_err(|_| invalid("resources"))?,
+        );
+
+        for record in &self.tasks {
+            let mut task = Task::new(record.name.clone());
+            task.edit_description(record.description.clone());
+            if record.completed {
+                task.toggle_completed();
+            }
+            if let Some(start) = record.start {
+                task.edit_start(start).map_err(|_| {
+                    FileError::Invalid(format!("task \"{}\" has an invalid start", record.name))
+                })?;
+            }
+            if let Some(finish) = record.finish {
+                task.edit_finish(finish).map_err(|_| {
+                    FileError::Invalid(format!("task \"{}\" has an invalid finish", record.name))
+                })?;
+            } else if let Some(minutes) = record.duration_minutes {
+                // planter-core only keeps whole hours, and the extras keep
+                // the exact duration.
+                let hours = (minutes + 59) / 60;
+                let duration =
+                    PositiveDuration::parse_from_str(&format!("{hours} h")).map_err(|_| {
+                        FileError::Invalid(format!(
+                            "task \"{}\" has an invalid duration",
+                            record.name
+                        ))
+                    })?;
+                task.edit_duration(duration);
+            }
+            project.add_task(task);
+        }
+
+        for (i, record) in self.tasks.iter().enumerate() {
+            project
+                .update_predecessors(i, &record.predecessors)
+                .map_err(|_| {
+                    FileError::Invalid(format!("task \"{}\" has invalid predecessors", record.name))
+                })?;
+            for link in &record.links {
+                if !record.predecessors.contains(&link.predecessor) {
+                    return Err(FileError::Invalid(format!(
+                        "task \"{}\" has a link from a task that isn't a predecessor",
+                        record.name
+                    )));
+                }
+                extras.set_link(
+                    link.predecessor,
+                    i,
+                    Link {
+                        kind: link.kind,
+                        lag: TimeDelta::minutes(link.lag_minutes),
+                    },
+                );
+            }
+            if record.parent.is_some_and(|p| p >= self.tasks.len())
+                || extras.set_parent(i, record.parent).is_err()
+            {
+                return Err(FileError::Invalid(format!(
+                    "task \"{}\" is nested under an invalid task",
+                    record.name
+                )));
+            }
+            let assignments = record
+                .assignments
+                .iter()
+                .map(|a| {
+                    (
+                        a.resource,
+                        Assignment {
+                            units: a.units,
+                            work: TimeDelta::minutes(a.work_minutes),
+                        },
+                    )
+                })
+                .collect::<Vec<_>>();
+            if assignments
+                .iter()
+                .any(|(r, a)| *r >= self.resources.len() || a.units == 0)
+            {
+                return Err(FileError::Invalid(format!(
+                    "task \"{}\" has an invalid assignment",
+                    record.name
+                )));
+            }
+            extras.set_assignments(i, &assignments);
+            extras.set_effort_driven(i, record.effort_driven);
+            if record.finish.is_none() {
+                extras.set_unscheduled_duration(i, record.duration_minutes.map(TimeDelta::minutes));
+            }
+            extras.set_milestone(i, record.milestone);
+            extras.set_milestone_duration(
+                i,
+                record.milestone_duration_minutes.map(TimeDelta::minutes),
+            );
+            if record.percent_complete > 100 {
+                return Err(FileError::Invalid(format!(
+                    "task \"{}\" has an invalid percent complete",
+                    record.name
+                )));
+            }
+            extras.set_progress(
+                i,
+                Progress {
+                    percent_complete: if record.completed {
+                        100
+                    } else {
+                        record.percent_complete
+                    },
+                    actual_start: record.actual_start,
+                    actual_finish: record.actual_finish,
+                },
+            );
+            extras.set_constraint(
+                i,
+                record.constraint.map(|c| Constraint {
+                    kind: c.kind,
+                    date: c.date,
+                }),
+            );
+            extras.set_deadline(i, record.deadline);
+        }
+
+        for (res_id, record) in self.resources.into_iter().enumerate() {
+            match record {
+                ResourceRecord::Personnel {
+                    first_name,
+                    last_name,
+                    email,
+                    phone,
+                    hourly_rate,
+                    days_off,
+                } => {
+                    let mut person = Person::new(&first_name, &last_name).ok_or_else(|| {
+                        FileError::Invalid(format!("invalid name \"{first_name} {last_name}\""))
+                    })?;
+                    if let Some(email) = email {
+                        person.update_email(EmailAddress::from_str(&email).map_err(|_| {
+                            FileError::Invalid(format!("invalid email \"{email}\""))
+                        })?);
+                    }
+                    if let Some(phone) = phone {
+                        person.update_phone(PhoneNumber::from_str(&phone).map_err(|_| {
+                            FileError::Invalid(format!("invalid phone number \"{phone}\""))
+                        })?);
+                    }
+                    project.add_resource(Resource::Personnel {
+                        person,
+                        hourly_rate,
+                    });
+                    extras.set_days_off(res_id, days_off.into_iter().collect());
+                }
+                ResourceRecord::Consumable {
+                    name,
+                    quantity,
+                    cost_per_unit,
+                } => {
+                    add_material(&mut project, &name, true, quantity, cost_per_unit);
+                }
+                ResourceRecord::NonConsumable {
+                    name,
+                    quantity,
+                    hourly_rate,
+                } => {
+                    add_material(&mut project, &name, false, quantity, hourly_rate);
+                }
+            }
+        }
+
+        Ok((project, extras))
+    }
+}
+
+/// Reads a project file, rejecting files written by newer releases.
+pub fn read(path: &Path) -> Result<ProjectFile, FileError> {
+    let content = fs::read_to_string(path).map_err(|e| FileError::Io(e.to_string()))?;
+    let file: ProjectFile =
+        serde_json::from_str(&content).map_err(|e| FileError::Malformed(e.to_string()))?;
+    if file.version > FORMAT_VERSION {
+        return Err(FileError::UnsupportedVersion(file.version));
+    }
+
+    Ok(file)
+}
+
+impl CalendarRecord {
+    fn to_calendar(&self) -> Result<Calendar, FileError> {
+        let invalid = || FileError::Invalid("the calendar is invalid".to_owned());
+
+        let mut calendar = Calendar::default();
+        // Working days are added before the others are removed, since some
+        // day must always be worked.
+        for weekday in &self.working_days {
+            calendar
+                .set_works_on(*weekday, true)
+                .map_err(|_| invalid())?;
+        }
+        for weekday in WEEKDAYS {
+            if !self.working_days.contains(&weekday) {
+                calendar
+                    .set_works_on(weekday, false)
+                    .map_err(|_| invalid())?;
+            }
+        }
+        calendar
+            .set_working_hours(self.working_hours.clone())
+            .map_err(|_| invalid())?;
+        for &holiday in &self.holidays {
+            calendar.add_holiday(holiday);
+        }
+        calendar.set_time_zone(self.time_zone);
+
+        Ok(calendar)
+    }
+}
+
+impl DurationFormatRecord {
+    fn to_duration_format(&self) -> Result<DurationFormat, FileError> {
+        let invalid = || FileError::Invalid("the duration format is invalid".to_owned());
+
+        let mut duration_format = DurationFormat {
+            unit: self.unit,
+            ..Default::default()
+        };
+        duration_format
+            .set_hours_per_day(TimeDelta::minutes(self.minutes_per_day))
+            .map_err(|_| invalid())?;
+        duration_format
+            .set_days_per_week(self.days_per_week)
+            .map_err(|_| invalid())?;
+
+        Ok(duration_format)
+    }
+}
+
+pub fn write(path: &Path, file: &ProjectFile) -> Result<(), FileError> {
+    let content =
+        serde_json::to_string_pretty(file).map_err(|e| FileError::Malformed(e.to_string()))?;
+    fs::write(path, content).map_err(|e| FileError::Io(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use chrono::{NaiveDate, TimeDelta, Utc, Weekday};
+    use chrono_tz::Tz;
+    use planter_core::{
+        person::Person,
+        project::Project,
+        resources::{Material, NonConsumable, Resource},
+        task::Task,
+    };
+
+    use super::ProjectFile;
+    use crate::{
+        calendar::Calendar,
+        duration_format::{DurationFormat, DurationUnit},
+        extras::{Assignment, Ids, Progress, ProjectExtras},
+        scheduling::{Constraint, ConstraintKind, Link, LinkKind},
+    };
+
+    #[test]
+    fn project_file_round_trips() {
+        let mut project = Project::new("World conquer");
+        project.add_task(Task::new("Build an army".to_owned()));
+        project.add_task(Task::new("Invade".to_owned()));
+        project.update_predecessors(1, &[0]).unwrap();
+        let mut extras = ProjectExtras::default();
+        let link = Link {
+            kind: LinkKind::StartToStart,
+            lag: TimeDelta::hours(-4),
+        };
+        extras.set_link(0, 1, link);
+        extras.set_parent(1, Some(0)).unwrap();
+        project.add_resource(Resource::Material(Material::NonConsumable(
+            NonConsumable::new("Swords"),
+        )));
+        let assignment = Assignment {
+            units: 50,
+            work: TimeDelta::hours(20),
+        };
+        extras.set_assignments(0, &[(0, assignment)]);
+        extras.set_effort_driven(0, true);
+        extras.set_unscheduled_duration(0, Some(TimeDelta::minutes(90)));
+        extras.set_milestone(1, true);
+        extras.set_milestone_duration(1, Some(TimeDelta::minutes(90)));
+        let progress = Progress {
+            percent_complete: 40,
+            actual_start: Some(Utc::now()),
+            actual_finish: None,
+        };
+        extras.set_progress(0, progress);
+        let constraint = Constraint {
+            kind: ConstraintKind::StartNoEarlierThan,
+            date: Utc::now(),
+        };
+        extras.set_constraint(0, Some(constraint));
+        extras.set_deadline(1, Some(Utc::now()));
+        let mut calendar = Calendar::default();
+        calendar.set_works_on(Weekday::Sat, true).unwrap();
+        calendar.add_holiday(NaiveDate::from_ymd_opt(2025, 12, 25).unwrap());
+        calendar.set_time_zone(Tz::America__New_York);
+        extras.set_calendar(calendar.clone());
+        project.add_resource(Resource::Personnel {
+            person: Person::new("Ada", "Lovelace").unwrap(),
+            hourly_rate: None,
+        });
+        let days_off = BTreeSet::from([NaiveDate::from_ymd_opt(2025, 8, 15).unwrap()]);
+        extras.set_days_off(1, days_off.clone());
+        let mut duration_format = DurationFormat {
+            unit: DurationUnit::Days,
+            ..Default::default()
+        };
+        duration_format
+            .set_hours_per_day(TimeDelta::minutes(450))
+            .unwrap();
+        extras.set_duration_format(duration_format);
+        let task_ids = Ids::new(vec![4, 1], 6).unwrap();
+        extras.set_task_ids(task_ids.clone());
+
+        let json = serde_json::to_string(&ProjectFile::from_project(&project, &extras)).unwrap();
+        let file: ProjectFile = serde_json::from_str(&json).unwrap();
+        let (loaded, loaded_extras) = file.into_project().unwrap();
+
+        assert_eq!(loaded.tasks().count(), 2);
+        assert_eq!(loaded.task(1).unwrap().name(), "Invade");
+        assert_eq!(loaded.predecessors_indices(1).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(loaded_extras.link(0, 1), link);
+        assert_eq!(loaded_extras.parent(1), Some(0));
+        assert_eq!(
+            loaded_extras.assignments(0).collect::<Vec<_>>(),
+            vec![(0, assignment)]
+        );
+        assert!(loaded_extras.is_effort_driven(0));
+        assert_eq!(
+            loaded_extras.unscheduled_duration(0),
+            Some(TimeDelta::minutes(90))
+        );
+        assert!(loaded_extras.is_milestone(1));
+        assert_eq!(
+            loaded_extras.milestone_duration(1),
+            Some(TimeDelta::minutes(90))
+        );
+        assert_eq!(loaded_extras.progress(0), progress);
+        assert_eq!(loaded_extras.constraint(0), Some(constraint));
+        assert_eq!(loaded_extras.deadline(1), extras.deadline(1));
+        assert_eq!(loaded_extras.calendar(), &calendar);
+        assert_eq!(loaded_extras.days_off(1).collect::<BTreeSet<_>>(), days_off);
+        assert_eq!(loaded_extras.duration_format(), &duration_format);
+        assert_eq!(loaded_extras.task_ids(), &task_ids);
+        assert_eq!(loaded_extras.resource_ids().to_vec(2), (vec![0, 1], 2));
+    }
+}
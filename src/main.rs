@@ -1,7 +1,9 @@
+use std::{mem::Discriminant, path::PathBuf};
+
 use iced::{
-    Color, Element, Length, Task,
+    Color, Element, Event, Length, Subscription, Task, event, keyboard,
     widget::{
-        PaneGrid, button,
+        PaneGrid, button, column,
         pane_grid::{self, DragEvent},
         row, scrollable, text,
     },
@@ -9,33 +11,80 @@ use iced::{
 use planter_core::project::Project;
 use ui::{personnel_page, tasks_page};
 
-use crate::ui::{
-    materials_page::{self, MaterialsMessage, MaterialsState},
-    personnel_page::{PersonnelMessage, PersonnelState},
-    tasks_page::{TasksMessage, TasksState},
+use crate::{
+    extras::ProjectExtras,
+    history::History,
+    io::{
+        FileError,
+        csv::{self, CsvTable},
+        ics::{self, DaysOff},
+        mspdi::{self, MspdiDocument},
+        native::{self, ProjectFile},
+    },
+    ui::{
+        calendar_page::{self, CalendarMessage, CalendarState},
+        csv_import::{self, CsvGrid, CsvImportMessage, CsvImportState},
+        dialogs,
+        gantt_page::{self, GanttMessage, GanttState},
+        materials_page::{self, MaterialsMessage, MaterialsState},
+        personnel_page::{PersonnelMessage, PersonnelState},
+        tasks_page::{TasksMessage, TasksState},
+        wbs_page::{self, WbsMessage, WbsState},
+    },
 };
 
+mod calendar;
+mod date_input;
+mod duration_format;
+mod extras;
+mod history;
+mod io;
+mod scheduling;
 mod ui;
 
 fn main() -> iced::Result {
-    iced::application(Appstate::default, update, view).run()
+    iced::application(Appstate::default, update, view)
+        .title(title)
+        .subscription(subscription)
+        .run()
 }
 
 struct Appstate {
     project: Project,
+    extras: ProjectExtras,
     tasks_state: TasksState,
     personnel_state: PersonnelState,
     materials_state: MaterialsState,
+    gantt_state: GanttState,
+    wbs_state: WbsState,
+    calendar_state: CalendarState,
     panes: pane_grid::State<Pane>,
     focus: Option<pane_grid::Pane>,
+    file_path: Option<PathBuf>,
+    file_error: Option<String>,
+    import_warnings: Vec<String>,
+    csv_import: Option<CsvImportState>,
+    history: History<ProjectFile, TypedCell>,
 }
 
-#[derive(Default)]
+/// A cell edits are typed into, by page, kind of edit and row.
+#[derive(Debug, PartialEq, Eq)]
+enum TypedCell {
+    Tasks(Discriminant<TasksMessage>, usize),
+    Personnel(Discriminant<PersonnelMessage>, usize),
+    Materials(Discriminant<MaterialsMessage>, usize),
+    Calendar(Discriminant<CalendarMessage>),
+}
+
+#[derive(Default, Clone, Copy)]
 enum PaneType {
     #[default]
     Tasks,
     Personnel,
     Materials,
+    Gantt,
+    Wbs,
+    Calendar,
 }
 
 #[derive(Clone, Debug)]
@@ -46,7 +95,32 @@ enum AppMessage {
     TasksMessage(TasksMessage),
     PersonnelMessage(PersonnelMessage),
     MaterialsMessage(MaterialsMessage),
+    GanttMessage(GanttMessage),
+    WbsMessage(WbsMessage),
+    CalendarMessage(CalendarMessage),
     ResourceDeleted(usize),
+    /// Reads days off from an iCalendar file, for the whole project or for the
+    /// person with the given resource index.
+    ImportIcs(Option<usize>),
+    IcsImported(Option<usize>, Option<Result<DaysOff, FileError>>),
+    NewProject,
+    OpenProject,
+    ProjectOpened(Option<Result<(PathBuf, ProjectFile), FileError>>),
+    SaveProject,
+    SaveProjectAs,
+    ProjectSaved(Option<Result<PathBuf, FileError>>),
+    ImportMspdi,
+    MspdiImported(Option<Result<MspdiDocument, FileError>>),
+    DismissImportWarnings,
+    ExportMspdi,
+    MspdiExported(Option<Result<PathBuf, FileError>>),
+    ExportCsv(CsvGrid),
+    CsvExported(Option<Result<PathBuf, FileError>>),
+    ImportCsv(CsvGrid),
+    CsvLoaded(CsvGrid, Option<Result<CsvTable, FileError>>),
+    CsvImportMessage(CsvImportMessage),
+    Undo,
+    Redo,
     // Close(pane_grid::Pane),
     Restore,
     Maximize(pane_grid::Pane),
@@ -59,7 +133,28 @@ struct Pane {
     pane_type: PaneType,
 }
 
+/// Applies a message, and records the changes it makes to the project in the
+/// history.
 fn update(state: &mut Appstate, message: AppMessage) -> Task<AppMessage> {
+    if !message.is_edit() {
+        return apply(state, message);
+    }
+
+    // Typing on in the same cell would be undone with the last change anyway.
+    let typed_cell = message.typed_cell();
+    if state.history.is_typing_on(typed_cell.as_ref()) {
+        return apply(state, message);
+    }
+
+    let before = state.snapshot();
+    let task = apply(state, message);
+    if state.snapshot() != before {
+        state.history.record(before, typed_cell);
+    }
+    task
+}
+
+fn apply(state: &mut Appstate, message: AppMessage) -> Task<AppMessage> {
     match message {
         AppMessage::PaneDragged(drag_event) => {
             if let DragEvent::Dropped { pane, target } = drag_event {
@@ -72,7 +167,21 @@ fn update(state: &mut Appstate, message: AppMessage) -> Task<AppMessage> {
             Task::none()
         }
         AppMessage::TasksMessage(tasks_message) => {
-            tasks_page::update(&mut state.tasks_state, &mut state.project, tasks_message);
+            if let TasksMessage::DeleteTask(i) = tasks_message {
+                wbs_page::update(
+                    &mut state.wbs_state,
+                    &mut state.tasks_state,
+                    &mut state.project,
+                    &mut state.extras,
+                    WbsMessage::TaskDeleted(i),
+                );
+            }
+            tasks_page::update(
+                &mut state.tasks_state,
+                &mut state.project,
+                &mut state.extras,
+                tasks_message,
+            );
             Task::none()
         }
         AppMessage::PersonnelMessage(personnel_message) => personnel_page::update(
@@ -85,6 +194,39 @@ fn update(state: &mut Appstate, message: AppMessage) -> Task<AppMessage> {
             &mut state.project,
             materials_message,
         ),
+        AppMessage::GanttMessage(gantt_message) => {
+            gantt_page::update(
+                &mut state.gantt_state,
+                &mut state.tasks_state,
+                &mut state.project,
+                &mut state.extras,
+                gantt_message,
+            );
+            Task::none()
+        }
+        AppMessage::WbsMessage(wbs_message) => {
+            wbs_page::update(
+                &mut state.wbs_state,
+                &mut state.tasks_state,
+                &mut state.project,
+                &mut state.extras,
+                wbs_message,
+            );
+            Task::none()
+        }
+        AppMessage::CalendarMessage(CalendarMessage::ImportHolidays) => {
+            Task::done(AppMessage::ImportIcs(None))
+        }
+        AppMessage::CalendarMessage(calendar_message) => {
+            calendar_page::update(
+                &mut state.calendar_state,
+                &mut state.tasks_state,
+                &mut state.project,
+                &mut state.extras,
+                calendar_message,
+            );
+            Task::none()
+        }
         AppMessage::PaneClicked(pane) => {
             state.focus = Some(pane);
             Task::none()
@@ -114,13 +256,348 @@ fn update(state: &mut Appstate, message: AppMessage) -> Task<AppMessage> {
                 &mut state.project,
                 PersonnelMessage::ResourceDeleted(res_id),
             );
+            tasks_page::update(
+                &mut state.tasks_state,
+                &mut state.project,
+                &mut state.extras,
+                TasksMessage::ResourceDeleted(res_id),
+            );
 
             Task::batch([task1, task2])
         }
+        AppMessage::ImportIcs(res_id) => Task::perform(
+            async {
+                let path = dialogs::pick_file("iCalendar", &[ics::EXTENSION]).await?;
+                Some(ics::read(&path))
+            },
+            move |result| AppMessage::IcsImported(res_id, result),
+        ),
+        AppMessage::IcsImported(res_id, result) => {
+            match result {
+                Some(Ok(days_off)) => {
+                    match res_id {
+                        None => calendar_page::update(
+                            &mut state.calendar_state,
+                            &mut state.tasks_state,
+                            &mut state.project,
+                            &mut state.extras,
+                            CalendarMessage::AddHolidays(days_off.dates),
+                        ),
+                        Some(res_id) => {
+                            let dates = state
+                                .extras
+                                .days_off(res_id)
+                                .chain(days_off.dates)
+                                .collect();
+                            tasks_page::update(
+                                &mut state.tasks_state,
+                                &mut state.project,
+                                &mut state.extras,
+                                TasksMessage::UpdateDaysOff(res_id, dates),
+                            );
+                        }
+                    }
+                    state.import_warnings = days_off.warnings;
+                }
+                Some(Err(e)) => state.file_error = Some(e.to_string()),
+                None => {}
+            }
+            Task::none()
+        }
+        AppMessage::NewProject => {
+            state.load_project(Project::new("New project"), ProjectExtras::default());
+            state.file_path = None;
+            Task::none()
+        }
+        AppMessage::OpenProject => Task::perform(
+            async {
+                let path = dialogs::pick_file("PlanTer project", &[native::EXTENSION]).await?;
+                Some(native::read(&path).map(|file| (path, file)))
+            },
+            AppMessage::ProjectOpened,
+        ),
+        AppMessage::ProjectOpened(result) => {
+            match result {
+                Some(Ok((path, file))) => match file.into_project() {
+                    Ok((project, extras)) => {
+                        state.load_project(project, extras);
+                        state.file_path = Some(path);
+                    }
+                    Err(e) => state.file_error = Some(e.to_string()),
+                },
+                Some(Err(e)) => state.file_error = Some(e.to_string()),
+                None => {}
+            }
+            Task::none()
+        }
+        AppMessage::SaveProject => {
+            if let Some(path) = state.file_path.clone() {
+                let file = ProjectFile::from_project(&state.project, &state.extras);
+                Task::perform(
+                    async move { Some(native::write(&path, &file).map(|_| path)) },
+                    AppMessage::ProjectSaved,
+                )
+            } else {
+                Task::done(AppMessage::SaveProjectAs)
+            }
+        }
+        AppMessage::SaveProjectAs => {
+            let file = ProjectFile::from_project(&state.project, &state.extras);
+            Task::perform(
+                async move {
+                    let mut path =
+                        dialogs::pick_save_path("PlanTer project", &[native::EXTENSION]).await?;
+                    if path.extension().is_none() {
+                        path.set_extension(native::EXTENSION);
+                    }
+                    Some(native::write(&path, &file).map(|_| path))
+                },
+                AppMessage::ProjectSaved,
+            )
+        }
+        AppMessage::ProjectSaved(result) => {
+            match result {
+                Some(Ok(path)) => {
+                    state.file_path = Some(path);
+                    state.file_error = None;
+                }
+                Some(Err(e)) => state.file_error = Some(e.to_string()),
+                None => {}
+            }
+            Task::none()
+        }
+        AppMessage::ImportMspdi => Task::perform(
+            async {
+                let path = dialogs::pick_file("Microsoft Project XML", &[mspdi::EXTENSION]).await?;
+                Some(mspdi::read(&path))
+            },
+            AppMessage::MspdiImported,
+        ),
+        AppMessage::MspdiImported(result) => {
+            match result {
+                Some(Ok(document)) => {
+                    let (project, extras, report) = mspdi::import(document);
+                    state.load_project(project, extras);
+                    state.file_path = None;
+                    state.import_warnings = report.warnings;
+                }
+                Some(Err(e)) => state.file_error = Some(e.to_string()),
+                None => {}
+            }
+            Task::none()
+        }
+        AppMessage::DismissImportWarnings => {
+            state.import_warnings.clear();
+            Task::none()
+        }
+        AppMessage::ExportMspdi => {
+            let document = mspdi::export(&state.project, &state.extras);
+            Task::perform(
+                async move {
+                    let mut path =
+                        dialogs::pick_save_path("Microsoft Project XML", &[mspdi::EXTENSION])
+                            .await?;
+                    if path.extension().is_none() {
+                        path.set_extension(mspdi::EXTENSION);
+                    }
+                    Some(mspdi::write(&path, &document).map(|_| path))
+                },
+                AppMessage::MspdiExported,
+            )
+        }
+        AppMessage::MspdiExported(result) | AppMessage::CsvExported(result) => {
+            match result {
+                Some(Ok(_)) => state.file_error = None,
+                Some(Err(e)) => state.file_error = Some(e.to_string()),
+                None => {}
+            }
+            Task::none()
+        }
+        AppMessage::ExportCsv(grid) => {
+            let table = match grid {
+                CsvGrid::Tasks => tasks_page::csv_table(&state.tasks_state),
+                CsvGrid::Personnel => {
+                    personnel_page::csv_table(&state.personnel_state, &state.extras)
+                }
+                CsvGrid::Materials => {
+                    materials_page::csv_table(&state.materials_state, &state.extras)
+                }
+            };
+            Task::perform(
+                async move {
+                    let mut path = dialogs::pick_save_path("CSV", &[csv::EXTENSION]).await?;
+                    if path.extension().is_none() {
+                        path.set_extension(csv::EXTENSION);
+                    }
+                    Some(csv::write(&path, &table).map(|_| path))
+                },
+                AppMessage::CsvExported,
+            )
+        }
+        AppMessage::ImportCsv(grid) => Task::perform(
+            async {
+                let path = dialogs::pick_file("CSV", &[csv::EXTENSION]).await?;
+                Some(csv::read(&path))
+            },
+            move |result| AppMessage::CsvLoaded(grid, result),
+        ),
+        AppMessage::CsvLoaded(grid, result) => {
+            match result {
+                Some(Ok(table)) => state.csv_import = Some(CsvImportState::new(grid, table)),
+                Some(Err(e)) => state.file_error = Some(e.to_string()),
+                None => {}
+            }
+            Task::none()
+        }
+        AppMessage::CsvImportMessage(CsvImportMessage::Cancel) => {
+            state.csv_import = None;
+            Task::none()
+        }
+        AppMessage::CsvImportMessage(CsvImportMessage::Confirm) => {
+            if let Some(csv_import) = state.csv_import.take() {
+                let rows = csv_import.mapped_rows();
+                state.import_warnings = match csv_import.grid {
+                    CsvGrid::Tasks => tasks_page::import_csv(
+                        &mut state.tasks_state,
+                        &mut state.project,
+                        &mut state.extras,
+                        &rows,
+                    ),
+                    CsvGrid::Personnel => personnel_page::import_csv(
+                        &mut state.personnel_state,
+                        &mut state.project,
+                        &rows,
+                    ),
+                    CsvGrid::Materials => materials_page::import_csv(
+                        &mut state.materials_state,
+                        &mut state.project,
+                        &rows,
+                    ),
+                };
+            }
+            Task::none()
+        }
+        AppMessage::CsvImportMessage(csv_import_message) => {
+            if let Some(csv_import) = &mut state.csv_import {
+                csv_import::update(csv_import, csv_import_message);
+            }
+            Task::none()
+        }
+        AppMessage::Undo => {
+            let current = state.snapshot();
+            if let Some(previous) = state.history.undo(current) {
+                state.restore(previous);
+            }
+            Task::none()
+        }
+        AppMessage::Redo => {
+            let current = state.snapshot();
+            if let Some(next) = state.history.redo(current) {
+                state.restore(next);
+            }
+            Task::none()
+        }
+    }
+}
+
+/// Ctrl+Z undoes, and Ctrl+Shift+Z or Ctrl+Y redo, even while a cell has the
+/// focus.
+fn subscription(_app_state: &Appstate) -> Subscription<AppMessage> {
+    event::listen_with(|event, _status, _window| match event {
+        Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+            if modifiers.command() =>
+        {
+            match key.as_ref() {
+                keyboard::Key::Character(c) if c.eq_ignore_ascii_case("z") => {
+                    Some(if modifiers.shift() {
+                        AppMessage::Redo
+                    } else {
+                        AppMessage::Undo
+                    })
+                }
+                keyboard::Key::Character(c) if c.eq_ignore_ascii_case("y") => {
+                    Some(AppMessage::Redo)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+fn title(app_state: &Appstate) -> String {
+    match &app_state.file_path {
+        Some(path) => format!(
+            "PlanTer - {}",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ),
+        None => format!("PlanTer - {}", app_state.project.name()),
     }
 }
 
 fn view(app_state: &Appstate) -> Element<'_, AppMessage> {
+    column![
+        view_file_menu(app_state),
+        view_import_warnings(app_state),
+        app_state
+            .csv_import
+            .as_ref()
+            .map(|csv_import| csv_import::view(csv_import).map(AppMessage::from)),
+        view_panes(app_state)
+    ]
+    .into()
+}
+
+fn view_file_menu(app_state: &Appstate) -> Element<'_, AppMessage> {
+    let menu_button = |label| button(text(label).size(14)).padding(3);
+    let error = app_state
+        .file_error
+        .as_ref()
+        .map(|e| text(e).size(14).style(text::danger));
+
+    row![
+        menu_button("New").on_press(AppMessage::NewProject),
+        menu_button("Open").on_press(AppMessage::OpenProject),
+        menu_button("Save").on_press(AppMessage::SaveProject),
+        menu_button("Save As").on_press(AppMessage::SaveProjectAs),
+        menu_button("Import MS Project").on_press(AppMessage::ImportMspdi),
+        menu_button("Export MS Project").on_press(AppMessage::ExportMspdi),
+        menu_button("Undo")
+            .on_press_maybe(app_state.history.can_undo().then_some(AppMessage::Undo)),
+        menu_button("Redo")
+            .on_press_maybe(app_state.history.can_redo().then_some(AppMessage::Redo)),
+        error,
+    ]
+    .spacing(5)
+    .padding(5)
+    .into()
+}
+
+fn view_import_warnings(app_state: &Appstate) -> Option<Element<'_, AppMessage>> {
+    if app_state.import_warnings.is_empty() {
+        return None;
+    }
+
+    let warnings = app_state
+        .import_warnings
+        .iter()
+        .map(|w| text(w).size(14).into());
+    let dismiss = button(text("Dismiss").size(14))
+        .style(button::secondary)
+        .padding(3)
+        .on_press(AppMessage::DismissImportWarnings);
+
+    Some(
+        column![text("Some data couldn't be imported:").size(14)]
+            .extend(warnings)
+            .push(dismiss)
+            .spacing(2)
+            .padding(5)
+            .into(),
+    )
+}
+
+fn view_panes(app_state: &Appstate) -> Element<'_, AppMessage> {
     let focus = app_state.focus;
     let total_panes = app_state.panes.len();
 
@@ -134,11 +611,37 @@ fn view(app_state: &Appstate) -> Element<'_, AppMessage> {
             ),
             PaneType::Personnel => (
                 "Personnel",
-                personnel_page::view(&app_state.personnel_state).map(AppMessage::from),
+                personnel_page::view(&app_state.personnel_state, &app_state.extras)
+                    .map(AppMessage::from),
             ),
             PaneType::Materials => (
                 "Materials",
-                materials_page::view(&app_state.materials_state).map(AppMessage::from),
+                materials_page::view(&app_state.materials_state, &app_state.extras)
+                    .map(AppMessage::from),
+            ),
+            PaneType::Gantt => (
+                "Gantt",
+                gantt_page::view(
+                    &app_state.gantt_state,
+                    &app_state.project,
+                    &app_state.extras,
+                )
+                .map(AppMessage::from),
+            ),
+            PaneType::Wbs => (
+                "WBS",
+                wbs_page::view(
+                    &app_state.wbs_state,
+                    &app_state.tasks_state,
+                    &app_state.project,
+                    &app_state.extras,
+                )
+                .map(AppMessage::from),
+            ),
+            PaneType::Calendar => (
+                "Calendar",
+                calendar_page::view(&app_state.calendar_state, &app_state.extras)
+                    .map(AppMessage::from),
             ),
         };
         let title = row![text(title).color(if is_focused {
@@ -151,6 +654,7 @@ fn view(app_state: &Appstate) -> Element<'_, AppMessage> {
         let title_bar = pane_grid::TitleBar::new(title)
             .controls(pane_grid::Controls::new(view_controls(
                 id,
+                pane.pane_type,
                 total_panes,
                 pane.is_pinned,
                 is_maximized,
@@ -192,24 +696,133 @@ impl Appstate {
                 is_pinned: false,
                 pane_type: PaneType::Personnel,
             },
+        ) && let Some((pane, _)) = panes.split(
+            pane_grid::Axis::Horizontal,
+            pane,
+            Pane {
+                is_pinned: false,
+                pane_type: PaneType::Materials,
+            },
         ) {
             panes.split(
                 pane_grid::Axis::Horizontal,
                 pane,
                 Pane {
                     is_pinned: false,
-                    pane_type: PaneType::Materials,
+                    pane_type: PaneType::Calendar,
                 },
             );
         }
+        panes.split(
+            pane_grid::Axis::Horizontal,
+            pane,
+            Pane {
+                is_pinned: false,
+                pane_type: PaneType::Gantt,
+            },
+        );
+        panes.split(
+            pane_grid::Axis::Vertical,
+            pane,
+            Pane {
+                is_pinned: false,
+                pane_type: PaneType::Wbs,
+            },
+        );
 
         Appstate {
             panes,
             project: Project::new("World conquer"),
+            extras: ProjectExtras::default(),
             tasks_state: TasksState::default(),
             personnel_state: PersonnelState::default(),
             materials_state: MaterialsState::default(),
+            gantt_state: GanttState::default(),
+            wbs_state: WbsState::default(),
+            calendar_state: CalendarState::from_extras(&ProjectExtras::default()),
             focus: None,
+            file_path: None,
+            file_error: None,
+            import_warnings: Vec::new(),
+            csv_import: None,
+            history: History::default(),
+        }
+    }
+
+    /// Replaces the current project with another one, which starts its own
+    /// history.
+    fn load_project(&mut self, project: Project, extras: ProjectExtras) {
+        self.set_project(project, extras);
+        self.history = History::default();
+        self.file_error = None;
+        self.import_warnings.clear();
+    }
+
+    /// Replaces the current project, rebuilding the state of every page from it.
+    fn set_project(&mut self, project: Project, extras: ProjectExtras) {
+        self.tasks_state = TasksState::from_project(&project, &extras);
+        self.personnel_state = PersonnelState::from_project(&project);
+        self.materials_state = MaterialsState::from_project(&project);
+        self.wbs_state = WbsState::default();
+        self.calendar_state = CalendarState::from_extras(&extras);
+        self.project = project;
+        self.extras = extras;
+    }
+
+    /// The project as it would be saved, which is what the history keeps.
+    fn snapshot(&self) -> ProjectFile {
+        ProjectFile::from_project(&self.project, &self.extras)
+    }
+
+    /// Goes back or forward to a snapshot from the history, keeping the WBS
+    /// tree folded as it was.
+    fn restore(&mut self, snapshot: ProjectFile) {
+        let (project, extras) = snapshot
+            .into_project()
+            .expect("Snapshots of the project should always load back. This is a bug.");
+        let mut wbs_state = std::mem::take(&mut self.wbs_state);
+        wbs_state.remap(&self.extras, &extras, project.tasks().count());
+        self.set_project(project, extras);
+        self.wbs_state = wbs_state;
+    }
+}
+
+impl AppMessage {
+    /// Whether the message may change the project, and so be undone.
+    fn is_edit(&self) -> bool {
+        match self {
+            AppMessage::TasksMessage(message) => message.is_edit(),
+            AppMessage::PersonnelMessage(message) => message.is_edit(),
+            AppMessage::MaterialsMessage(message) => message.is_edit(),
+            AppMessage::GanttMessage(message) => message.is_edit(),
+            AppMessage::WbsMessage(message) => message.is_edit(),
+            AppMessage::CalendarMessage(message) => message.is_edit(),
+            AppMessage::IcsImported(..)
+            | AppMessage::CsvImportMessage(CsvImportMessage::Confirm) => true,
+            // Removing a resource from the tasks finishes its deletion, which
+            // has already been recorded.
+            AppMessage::ResourceDeleted(_) => false,
+            _ => false,
+        }
+    }
+
+    /// The cell the message types into, if any, so that typing in a cell is
+    /// undone at once.
+    fn typed_cell(&self) -> Option<TypedCell> {
+        match self {
+            AppMessage::TasksMessage(message) => message
+                .typed_row()
+                .map(|row| TypedCell::Tasks(std::mem::discriminant(message), row)),
+            AppMessage::PersonnelMessage(message) => message
+                .typed_row()
+                .map(|row| TypedCell::Personnel(std::mem::discriminant(message), row)),
+            AppMessage::MaterialsMessage(message) => message
+                .typed_row()
+                .map(|row| TypedCell::Materials(std::mem::discriminant(message), row)),
+            AppMessage::CalendarMessage(message) => message
+                .is_typed()
+                .then(|| TypedCell::Calendar(std::mem::discriminant(message))),
+            _ => None,
         }
     }
 }
@@ -238,6 +851,30 @@ impl From<MaterialsMessage> for AppMessage {
     }
 }
 
+impl From<GanttMessage> for AppMessage {
+    fn from(value: GanttMessage) -> Self {
+        AppMessage::GanttMessage(value)
+    }
+}
+
+impl From<WbsMessage> for AppMessage {
+    fn from(value: WbsMessage) -> Self {
+        AppMessage::WbsMessage(value)
+    }
+}
+
+impl From<CalendarMessage> for AppMessage {
+    fn from(value: CalendarMessage) -> Self {
+        AppMessage::CalendarMessage(value)
+    }
+}
+
+impl From<CsvImportMessage> for AppMessage {
+    fn from(value: CsvImportMessage) -> Self {
+        AppMessage::CsvImportMessage(value)
+    }
+}
+
 const PANE_ID_COLOR_UNFOCUSED: Color = Color::from_rgb(
     0xFF as f32 / 255.0,
     0xC7 as f32 / 255.0,
@@ -251,10 +888,30 @@ const PANE_ID_COLOR_FOCUSED: Color = Color::from_rgb(
 
 fn view_controls<'a>(
     pane: pane_grid::Pane,
+    pane_type: PaneType,
     total_panes: usize,
     is_pinned: bool,
     is_maximized: bool,
 ) -> Element<'a, AppMessage> {
+    let csv_grid = match pane_type {
+        PaneType::Tasks => Some(CsvGrid::Tasks),
+        PaneType::Personnel => Some(CsvGrid::Personnel),
+        PaneType::Materials => Some(CsvGrid::Materials),
+        PaneType::Gantt | PaneType::Wbs | PaneType::Calendar => None,
+    };
+    let csv_import = csv_grid.map(|grid| {
+        button(text("Import CSV").size(14))
+            .style(button::secondary)
+            .padding(3)
+            .on_press(AppMessage::ImportCsv(grid))
+    });
+    let csv_export = csv_grid.map(|grid| {
+        button(text("Export CSV").size(14))
+            .style(button::secondary)
+            .padding(3)
+            .on_press(AppMessage::ExportCsv(grid))
+    });
+
     let pin = button(text(if is_pinned { "Unpin" } else { "Pin" }).size(14))
         .on_press(AppMessage::TogglePin(pane))
         .padding(3);
@@ -285,7 +942,9 @@ fn view_controls<'a>(
     //         None
     //     });
 
-    row![pin, maximize].spacing(5).into()
+    row![csv_import, csv_export, pin, maximize]
+        .spacing(5)
+        .into()
 }
 
 mod style {
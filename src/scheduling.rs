@@ -0,0 +1,748 @@
+//! Dependency-driven scheduling.
+//!
+//! Tasks start as soon as all their links allow: by default a successor starts
+//! when its predecessor finishes, but links can also tie starts and finishes
+//! together, with a lag or a lead. Durations and lags are counted in the
+//! working time of the project calendar, less the days off of the people
+//! assigned to each task, and tasks that take some time start at the next
+//! working time. Tasks without predecessors keep the start
+//! they were given, and anchor the schedule, and so do tasks that have
+//! actually started. Milestones take no time, so their start is also their
+//! finish.
+//!
+//! Summary tasks span their children, which in turn inherit the links of
+//! their ancestors.
+//! Tasks without float form the critical path: delaying any of them delays
+//! the whole project.
+//!
+//! Constraints hold tasks to dates on top of their links: a task that must
+//! start on a date starts then, even if its links would rather start it
+//! later, and a task that can't start before a date waits for it. Dates a
+//! task must finish by, whether a constraint or a deadline, don't move it,
+//! but take away its float. Either way, tasks whose links push them past
+//! their dates are flagged, along with the link that drives them.
+
+use std::{borrow::Cow, fmt::Display};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use planter_core::project::Project;
+use serde::{Deserialize, Serialize};
+
+use crate::{calendar::Calendar, extras::ProjectExtras};
+
+/// A task, as seen by the scheduler.
+#[derive(Debug, Clone)]
+pub struct Activity {
+    pub start: Option<DateTime<Utc>>,
+    /// When the task actually started, if it has. Links can't move it anymore.
+    pub actual_start: Option<DateTime<Utc>>,
+    pub duration: TimeDelta,
+    pub predecessors: Vec<(usize, Link)>,
+    /// Tasks nested under this one, if it's a summary task.
+    pub children: Vec<usize>,
+    /// Working time of the task, if it isn't the project calendar because
+    /// some of its people have days off.
+    pub calendar: Option<Calendar>,
+    pub constraint: Option<Constraint>,
+    /// When the task should be finished by. Unlike constraints, deadlines
+    /// never move a task.
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+/// Which end of the predecessor drives which end of the successor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkKind {
+    #[default]
+    FinishToStart,
+    StartToStart,
+    FinishToFinish,
+    StartToFinish,
+}
+
+impl LinkKind {
+    pub const ALL: [LinkKind; 4] = [
+        LinkKind::FinishToStart,
+        LinkKind::StartToStart,
+        LinkKind::FinishToFinish,
+        LinkKind::StartToFinish,
+    ];
+
+    /// Whether the link starts from the start of the predecessor.
+    pub fn is_from_start(&self) -> bool {
+        matches!(self, LinkKind::StartToStart | LinkKind::StartToFinish)
+    }
+
+    /// Whether the link ends on the finish of the successor.
+    pub fn is_to_finish(&self) -> bool {
+        matches!(self, LinkKind::FinishToFinish | LinkKind::StartToFinish)
+    }
+}
+
+impl Display for LinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkKind::FinishToStart => f.write_str("FS"),
+            LinkKind::StartToStart => f.write_str("SS"),
+            LinkKind::FinishToFinish => f.write_str("FF"),
+            LinkKind::StartToFinish => f.write_str("SF"),
+        }
+    }
+}
+
+/// How a task is held to the date of its constraint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstraintKind {
+    /// No date: the task starts as soon as its links allow.
+    #[default]
+    AsSoonAsPossible,
+    MustStartOn,
+    StartNoEarlierThan,
+    FinishNoLaterThan,
+}
+
+impl ConstraintKind {
+    pub const ALL: [ConstraintKind; 4] = [
+        ConstraintKind::AsSoonAsPossible,
+        ConstraintKind::MustStartOn,
+        ConstraintKind::StartNoEarlierThan,
+        ConstraintKind::FinishNoLaterThan,
+    ];
+
+    /// Whether the date of the constraint is a finish, rather than a start.
+    pub fn is_finish(&self) -> bool {
+        matches!(self, ConstraintKind::FinishNoLaterThan)
+    }
+}
+
+impl Display for ConstraintKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConstraintKind::AsSoonAsPossible => f.write_str("ASAP"),
+            ConstraintKind::MustStartOn => f.write_str("MSO"),
+            ConstraintKind::StartNoEarlierThan => f.write_str("SNET"),
+            ConstraintKind::FinishNoLaterThan => f.write_str("FNLT"),
+        }
+    }
+}
+
+/// A date a task is held to, on top of its links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraint {
+    pub kind: ConstraintKind,
+    pub date: DateTime<Utc>,
+}
+
+/// A dependency between two tasks. A positive lag delays the successor, a
+/// negative one (a lead) lets it overlap its predecessor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Link {
+    pub kind: LinkKind,
+    pub lag: TimeDelta,
+}
+
+impl Link {
+    /// Earliest start of a successor lasting `duration`, given the early dates
+    /// of the predecessor.
+    fn earliest_start(
+        &self,
+        (start, finish): (DateTime<Utc>, DateTime<Utc>),
+        duration: TimeDelta,
+        calendar: &Calendar,
+    ) -> DateTime<Utc> {
+        let from = if self.kind.is_from_start() {
+            start
+        } else {
+            finish
+        };
+        let to = calendar.add(from, self.lag);
+        if self.kind.is_to_finish() {
+            calendar.add(to, -duration)
+        } else {
+            to
+        }
+    }
+
+    /// Latest finish of a predecessor lasting `duration`, given the late dates
+    /// of the successor.
+    fn latest_finish(
+        &self,
+        (start, finish): (DateTime<Utc>, DateTime<Utc>),
+        duration: TimeDelta,
+        calendar: &Calendar,
+    ) -> DateTime<Utc> {
+        let to = if self.kind.is_to_finish() {
+            finish
+        } else {
+            start
+        };
+        let from = calendar.add(to, -self.lag);
+        if self.kind.is_from_start() {
+            calendar.add(from, duration)
+        } else {
+            from
+        }
+    }
+}
+
+/// Earliest and latest dates of a task that don't delay the project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    pub early_start: DateTime<Utc>,
+    pub early_finish: DateTime<Utc>,
+    pub late_start: DateTime<Utc>,
+    pub late_finish: DateTime<Utc>,
+    /// How much working time the task can be delayed without delaying the
+    /// project.
+    pub total_float: TimeDelta,
+    /// How much working time the task can be delayed without delaying its
+    /// successors.
+    pub free_float: TimeDelta,
+    /// The predecessor, and its link, that sets the start of the task, or
+    /// would if its constraint let it.
+    pub driving_link: Option<(usize, Link)>,
+    /// Whether the links or the actual dates of the task break its
+    /// constraint.
+    pub misses_constraint: bool,
+    /// Whether the task finishes after its deadline.
+    pub misses_deadline: bool,
+}
+
+impl Schedule {
+    pub fn is_critical(&self) -> bool {
+        self.total_float <= TimeDelta::zero()
+    }
+}
+
+/// Working time a task takes. Milestones take none, and tasks that haven't
+/// been placed in time yet take their duration.
+pub fn task_duration(
+    project: &Project,
+    extras: &ProjectExtras,
+    task_index: usize,
+) -> Option<TimeDelta> {
+    let task = project.task(task_index)?;
+    match (task.start(), task.finish()) {
+        _ if extras.is_milestone(task_index) => Some(TimeDelta::zero()),
+        (Some(start), Some(finish)) => {
+            Some(extras.task_calendar(task_index).working_time(start, finish))
+        }
+        _ => extras
+            .unscheduled_duration(task_index)
+            .or_else(|| task.duration().map(|d| TimeDelta::hours(d.num_hours()))),
+    }
+}
+
+/// Schedules every task of the project. Tasks that can't be placed in time,
+/// because neither they nor their predecessors have a start, are `None`.
+pub fn schedule(project: &Project, extras: &ProjectExtras) -> Vec<Option<Schedule>> {
+    let task_count = project.tasks().count();
+    // Links between a task and one of its ancestors make no sense, since the
+    // dates of summary tasks come from their children.
+    let is_related =
+        |a: usize, b: usize| a == b || extras.is_ancestor(a, b) || extras.is_ancestor(b, a);
+
+    let activities = project
+        .tasks()
+        .enumerate()
+        .map(|(i, task)| Activity {
+            start: task.start(),
+            actual_start: extras.progress(i).actual_start,
+            duration: task_duration(project, extras, i).unwrap_or_default(),
+            predecessors: std::iter::once(i)
+                .chain(extras.ancestors(i))
+                .flat_map(|task| {
+                    project
+                        .predecessors_indices(task)
+                        .map(move |p| (p, extras.link(p, task)))
+                })
+                .filter(|(p, _)| !is_related(*p, i))
+                .collect(),
+            children: extras.children(Some(i), task_count),
+            calendar: match extras.task_calendar(i) {
+                Cow::Owned(calendar) => Some(calendar),
+                Cow::Borrowed(_) => None,
+            },
+            constraint: extras.constraint(i),
+            deadline: extras.deadline(i),
+        })
+        .collect::<Vec<Activity>>();
+
+    compute(&activities, extras.calendar())
+}
+
+/// Runs a forward pass for the early dates and a backward pass for the late
+/// dates. Activities without a calendar of their own follow `calendar`.
+pub fn compute(activities: &[Activity], calendar: &Calendar) -> Vec<Option<Schedule>> {
+    let Some(order) = topological_order(activities) else {
+        return vec![None; activities.len()];
+    };
+    let calendar_of = |i: usize| activities[i].calendar.as_ref().unwrap_or(calendar);
+
+    let mut early: Vec<Option<(DateTime<Utc>, DateTime<Utc>)>> = vec![None; activities.len()];
+    let mut driving_links = vec![None; activities.len()];
+    let mut misses_constraint = vec![false; activities.len()];
+    for &i in &order {
+        let activity = &activities[i];
+        if !activity.children.is_empty() {
+            early[i] = activity
+                .children
+                .iter()
+                .filter_map(|&c| early[c])
+                .reduce(|(s0, f0), (s1, f1)| (s0.min(s1), f0.max(f1)));
+            continue;
+        }
+        let calendar = calendar_of(i);
+        let driving_link = activity
+            .predecessors
+            .iter()
+            .filter_map(|&(p, link)| {
+                early[p].map(|dates| {
+                    let start = link.earliest_start(dates, activity.duration, calendar);
+                    (start, p, link)
+                })
+            })
+            .max_by_key(|(start, _, _)| *start);
+        let links_start = driving_link.map(|(start, _, _)| start);
+        let start = match activity.constraint {
+            Some(Constraint {
+                kind: ConstraintKind::MustStartOn,
+                date,
+            }) => Some(date),
+            Some(Constraint {
+                kind: ConstraintKind::StartNoEarlierThan,
+                date,
+            }) => Some(links_start.or(activity.start).map_or(date, |s| s.max(date))),
+            _ => links_start.or(activity.start),
+        };
+        // Links only drive tasks that haven't started, and that their
+        // constraint doesn't hold back any further.
+        let is_driven = activity.actual_start.is_none()
+            && links_start.is_some_and(|links_start| start.is_none_or(|s| links_start >= s));
+        let early_start = activity.actual_start.or(start).map(|start| {
+            if activity.duration > TimeDelta::zero() && activity.actual_start.is_none() {
+                calendar.next_working_time(start)
+            } else {
+                start
+            }
+        });
+        early[i] = early_start.map(|start| (start, calendar.add(start, activity.duration)));
+        driving_links[i] = driving_link
+            .filter(|_| is_driven)
+            .map(|(_, p, link)| (p, link));
+        misses_constraint[i] = match activity.constraint {
+            Some(Constraint {
+                kind: ConstraintKind::MustStartOn,
+                date,
+            }) => links_start
+                .into_iter()
+                .chain(activity.actual_start)
+                .any(|start| start > date),
+            Some(Constraint {
+                kind: ConstraintKind::FinishNoLaterThan,
+                date,
+            }) => early[i].is_some_and(|(_, finish)| finish > date),
+            _ => false,
+        };
+    }
+
+    let Some(project_finish) = early.iter().flatten().map(|(_, finish)| *finish).max() else {
+        return vec![None; activities.len()];
+    };
+
+    let mut successors = vec![Vec::new(); activities.len()];
+    let mut parents = vec![None; activities.len()];
+    for (i, activity) in activities.iter().enumerate() {
+        for &(p, link) in &activity.predecessors {
+            successors[p].push((i, link));
+        }
+        for &c in &activity.children {
+            parents[c] = Some(i);
+        }
+    }
+
+    // Summary tasks come after their children in the topological order, so
+    // they're scheduled first here, and bound the late dates of their children.
+    let mut schedule: Vec<Option<Schedule>> = vec![None; activities.len()];
+    for &i in order.iter().rev() {
+        let Some((early_start, early_finish)) = early[i] else {
+            continue;
+        };
+        let activity = &activities[i];
+        let calendar = calendar_of(i);
+        let duration = calendar.working_time(early_start, early_finish);
+        let parent = parents[i].and_then(|p| schedule[p].map(|dates| (p, dates)));
+        // Dates the task must finish by leave it less float, down to none or
+        // less if it misses them.
+        let finish_by = activity
+            .constraint
+            .and_then(|constraint| match constraint.kind {
+                ConstraintKind::MustStartOn => Some(calendar.add(constraint.date, duration)),
+                ConstraintKind::FinishNoLaterThan => Some(constraint.date),
+                _ => None,
+            })
+            .into_iter()
+            .chain(activity.deadline)
+            .filter(|_| activity.children.is_empty());
+        let late_finish = successors[i]
+            .iter()
+            .filter_map(|(s, link)| {
+                schedule[*s]
+                    .map(|s| link.latest_finish((s.late_start, s.late_finish), duration, calendar))
+            })
+            .chain(parent.map(|(_, dates)| dates.late_finish))
+            .chain(finish_by)
+            .fold(project_finish, DateTime::min);
+        // The latest finish that doesn't push back any successor from its early
+        // dates. Both are capped by the project finish, since links from the
+        // start of a task don't stop its finish from delaying the project.
+        let free_finish = successors[i]
+            .iter()
+            .filter_map(|(s, link)| {
+                schedule[*s].map(|s| {
+                    link.latest_finish((s.early_start, s.early_finish), duration, calendar)
+                })
+            })
+            .chain(
+                parent.map(|(p, dates)| calendar_of(p).add(dates.early_finish, dates.free_float)),
+            )
+            .fold(project_finish, DateTime::min);
+
+        let late_start = calendar.add(late_finish, -duration);
+        schedule[i] = Some(Schedule {
+            early_start,
+            early_finish,
+            late_start,
+            late_finish,
+            total_float: calendar.working_time(early_start, late_start),
+            free_float: calendar.working_time(early_finish, free_finish),
+            driving_link: driving_links[i],
+            misses_constraint: misses_constraint[i],
+            misses_deadline: activity.children.is_empty()
+                && activity
+                    .deadline
+                    .is_some_and(|deadline| early_finish > deadline),
+        });
+    }
+
+    schedule
+}
+
+/// Orders activities so that each comes after all its predecessors, and
+/// summary tasks after all their children. Returns
+/// `None` if the dependencies contain a cycle.
+fn topological_order(activities: &[Activity]) -> Option<Vec<usize>> {
+    let mut pending = activities
+        .iter()
+        .map(|a| a.predecessors.len() + a.children.len())
+        .collect::<Vec<usize>>();
+    let mut successors = vec![Vec::new(); activities.len()];
+    for (i, activity) in activities.iter().enumerate() {
+        for &p in activity
+            .predecessors
+            .iter()
+            .map(|(p, _)| p)
+            .chain(&activity.children)
+        {
+            successors[p].push(i);
+        }
+    }
+
+    let mut ready = (0..activities.len())
+        .filter(|&i| pending[i] == 0)
+        .collect::<Vec<usize>>();
+    let mut order = Vec::with_capacity(activities.len());
+    while let Some(i) = ready.pop() {
+        order.push(i);
+        for &s in &successors[i] {
+            pending[s] -= 1;
+            if pending[s] == 0 {
+                ready.push(s);
+            }
+        }
+    }
+
+    (order.len() == activities.len()).then_some(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+
+    use super::{Activity, Constraint, ConstraintKind, Link, LinkKind, Schedule};
+    use crate::calendar::Calendar;
+
+    fn date(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+    }
+
+    /// Schedules around the clock, so that durations are elapsed time.
+    fn compute(activities: &[Activity]) -> Vec<Option<Schedule>> {
+        super::compute(activities, &Calendar::continuous())
+    }
+
+    fn activity(start: Option<&str>, hours: i64, predecessors: &[usize]) -> Activity {
+        Activity {
+            start: start.map(date),
+            actual_start: None,
+            duration: TimeDelta::hours(hours),
+            predecessors: predecessors.iter().map(|&p| (p, Link::default())).collect(),
+            children: Vec::new(),
+            calendar: None,
+            constraint: None,
+            deadline: None,
+        }
+    }
+
+    fn summary(children: &[usize]) -> Activity {
+        Activity {
+            children: children.to_vec(),
+            ..activity(None, 0, &[])
+        }
+    }
+
+    fn linked(mut activity: Activity, kind: LinkKind, lag_hours: i64) -> Activity {
+        for (_, link) in &mut activity.predecessors {
+            *link = Link {
+                kind,
+                lag: TimeDelta::hours(lag_hours),
+            };
+        }
+        activity
+    }
+
+    #[test]
+    fn successors_start_when_predecessors_finish() {
+        let schedule = compute(&[
+            activity(Some("2025-01-01T08:00:00Z"), 8, &[]),
+            activity(Some("2024-01-01T08:00:00Z"), 4, &[0]),
+            activity(None, 2, &[0, 1]),
+        ]);
+
+        assert_eq!(
+            schedule[1].unwrap().early_start,
+            date("2025-01-01T16:00:00Z")
+        );
+        assert_eq!(
+            schedule[2].unwrap().early_start,
+            date("2025-01-01T20:00:00Z")
+        );
+    }
+
+    #[test]
+    fn late_dates_leave_slack_on_short_branches() {
+        // 0 -> 1 -> 3 and 0 -> 2 -> 3, where 2 is shorter than 1.
+        let schedule = compute(&[
+            activity(Some("2025-01-01T00:00:00Z"), 1, &[]),
+            activity(None, 10, &[0]),
+            activity(None, 4, &[0]),
+            activity(None, 1, &[1, 2]),
+        ]);
+
+        let short = schedule[2].unwrap();
+        assert_eq!(short.total_float, TimeDelta::hours(6));
+        assert_eq!(short.free_float, TimeDelta::hours(6));
+        assert!(!short.is_critical());
+        assert!(
+            [0, 1, 3]
+                .iter()
+                .all(|&i| schedule[i].unwrap().is_critical())
+        );
+    }
+
+    #[test]
+    fn free_float_only_counts_direct_successors() {
+        // 0 -> 1 -> 2, 0 -> 3 -> 2 and 0 -> 4, where 1 can slip until 3
+        // finishes without delaying 2, and further until 4 finishes without
+        // delaying the project.
+        let schedule = compute(&[
+            activity(Some("2025-01-01T00:00:00Z"), 1, &[]),
+            activity(None, 2, &[0]),
+            activity(None, 1, &[1, 3]),
+            activity(None, 10, &[0]),
+            activity(None, 20, &[0]),
+        ]);
+
+        let task = schedule[1].unwrap();
+        assert_eq!(task.free_float, TimeDelta::hours(8));
+        assert_eq!(task.total_float, TimeDelta::hours(17));
+        assert!(task.free_float < task.total_float);
+    }
+
+    #[test]
+    fn link_kinds_and_lags_are_respected() {
+        let first = || activity(Some("2025-01-01T00:00:00Z"), 10, &[]);
+        let early_start =
+            |successor: Activity| compute(&[first(), successor])[1].unwrap().early_start;
+
+        assert_eq!(
+            early_start(linked(activity(None, 4, &[0]), LinkKind::FinishToStart, -2)),
+            date("2025-01-01T08:00:00Z")
+        );
+        assert_eq!(
+            early_start(linked(activity(None, 4, &[0]), LinkKind::StartToStart, 3)),
+            date("2025-01-01T03:00:00Z")
+        );
+        assert_eq!(
+            early_start(linked(activity(None, 4, &[0]), LinkKind::FinishToFinish, 0)),
+            date("2025-01-01T06:00:00Z")
+        );
+        assert_eq!(
+            early_start(linked(activity(None, 4, &[0]), LinkKind::StartToFinish, 5)),
+            date("2025-01-01T01:00:00Z")
+        );
+    }
+
+    #[test]
+    fn start_to_start_links_dont_hide_the_project_finish() {
+        // 1 starts with 0 and is much shorter, so 0 still finishes the project.
+        let schedule = compute(&[
+            activity(Some("2025-01-01T00:00:00Z"), 10, &[]),
+            linked(activity(None, 2, &[0]), LinkKind::StartToStart, 0),
+        ]);
+
+        assert!(schedule[0].unwrap().is_critical());
+        let successor = schedule[1].unwrap();
+        assert_eq!(successor.total_float, TimeDelta::hours(8));
+        assert_eq!(successor.free_float, TimeDelta::hours(8));
+    }
+
+    #[test]
+    fn summary_tasks_span_their_children() {
+        // 2 summarizes 0 and 1, and 3 follows 2.
+        let schedule = compute(&[
+            activity(Some("2025-01-01T00:00:00Z"), 4, &[]),
+            activity(Some("2025-01-01T02:00:00Z"), 8, &[]),
+            summary(&[0, 1]),
+            activity(None, 1, &[2]),
+        ]);
+
+        let summary = schedule[2].unwrap();
+        assert_eq!(summary.early_start, date("2025-01-01T00:00:00Z"));
+        assert_eq!(summary.early_finish, date("2025-01-01T10:00:00Z"));
+        assert_eq!(
+            schedule[3].unwrap().early_start,
+            date("2025-01-01T10:00:00Z")
+        );
+        assert_eq!(schedule[0].unwrap().total_float, TimeDelta::hours(6));
+        assert!(schedule[1].unwrap().is_critical());
+    }
+
+    #[test]
+    fn started_tasks_stay_where_they_started() {
+        let started = Activity {
+            actual_start: Some(date("2025-01-01T04:00:00Z")),
+            ..activity(None, 2, &[0])
+        };
+        let schedule = compute(&[activity(Some("2025-01-01T00:00:00Z"), 8, &[]), started]);
+
+        assert_eq!(
+            schedule[1].unwrap().early_start,
+            date("2025-01-01T04:00:00Z")
+        );
+    }
+
+    #[test]
+    fn successors_wait_for_working_time() {
+        // Friday afternoon, then a weekend.
+        let schedule = super::compute(
+            &[
+                activity(Some("2025-01-03T13:00:00Z"), 4, &[]),
+                activity(None, 8, &[0]),
+            ],
+            &Calendar::default(),
+        );
+
+        let successor = schedule[1].unwrap();
+        assert_eq!(successor.early_start, date("2025-01-06T08:00:00Z"));
+        assert_eq!(successor.early_finish, date("2025-01-06T17:00:00Z"));
+        assert!(successor.is_critical());
+    }
+
+    #[test]
+    fn lags_are_working_time() {
+        // A Monday, so a day of lag is Tuesday, and the successor takes
+        // Wednesday.
+        let schedule = super::compute(
+            &[
+                activity(Some("2025-01-06T08:00:00Z"), 8, &[]),
+                linked(activity(None, 8, &[0]), LinkKind::FinishToStart, 8),
+            ],
+            &Calendar::default(),
+        );
+
+        assert_eq!(
+            schedule[1].unwrap().early_finish,
+            date("2025-01-08T17:00:00Z")
+        );
+    }
+
+    #[test]
+    fn tasks_wait_for_the_days_off_of_their_people() {
+        let mut calendar = Calendar::default();
+        calendar.add_holiday(NaiveDate::from_ymd_opt(2025, 1, 6).unwrap());
+        let schedule = super::compute(
+            &[
+                activity(Some("2025-01-03T13:00:00Z"), 4, &[]),
+                Activity {
+                    calendar: Some(calendar),
+                    ..activity(None, 8, &[0])
+                },
+                activity(None, 8, &[0]),
+            ],
+            &Calendar::default(),
+        );
+
+        assert_eq!(
+            schedule[1].unwrap().early_start,
+            date("2025-01-07T08:00:00Z")
+        );
+        assert_eq!(
+            schedule[2].unwrap().early_start,
+            date("2025-01-06T08:00:00Z")
+        );
+    }
+
+    #[test]
+    fn tasks_pushed_past_their_dates_are_flagged() {
+        let constrained = |kind, on: &str| Activity {
+            constraint: Some(Constraint {
+                kind,
+                date: date(on),
+            }),
+            ..activity(None, 2, &[0, 1])
+        };
+        let schedule = compute(&[
+            activity(Some("2025-01-01T00:00:00Z"), 8, &[]),
+            activity(Some("2025-01-01T00:00:00Z"), 4, &[]),
+            constrained(ConstraintKind::MustStartOn, "2025-01-01T06:00:00Z"),
+            constrained(ConstraintKind::StartNoEarlierThan, "2025-01-01T12:00:00Z"),
+            constrained(ConstraintKind::FinishNoLaterThan, "2025-01-01T09:00:00Z"),
+            Activity {
+                deadline: Some(date("2025-01-02T00:00:00Z")),
+                ..activity(None, 2, &[0])
+            },
+        ]);
+
+        let must_start = schedule[2].unwrap();
+        assert_eq!(must_start.early_start, date("2025-01-01T06:00:00Z"));
+        assert!(must_start.misses_constraint);
+        assert_eq!(must_start.driving_link, Some((0, Link::default())));
+        let no_earlier = schedule[3].unwrap();
+        assert_eq!(no_earlier.early_start, date("2025-01-01T12:00:00Z"));
+        assert!(!no_earlier.misses_constraint);
+        let finish_by = schedule[4].unwrap();
+        assert_eq!(finish_by.early_start, date("2025-01-01T08:00:00Z"));
+        assert!(finish_by.misses_constraint);
+        assert_eq!(finish_by.total_float, TimeDelta::hours(-1));
+        let deadline = schedule[5].unwrap();
+        assert!(!deadline.misses_deadline);
+        assert_eq!(deadline.total_float, TimeDelta::hours(4));
+    }
+
+    #[test]
+    fn unanchored_tasks_are_not_scheduled() {
+        let schedule = compute(&[activity(None, 1, &[])]);
+
+        assert!(schedule[0].is_none());
+    }
+}
@@ -1,6 +1,11 @@
+pub mod amount;
+pub mod calendar_page;
 pub mod components;
 pub mod constants;
+pub mod csv_import;
+pub mod dialogs;
+pub mod gantt_page;
 pub mod materials_page;
 pub mod personnel_page;
 pub mod tasks_page;
-pub mod wbs;
+pub mod wbs_page;
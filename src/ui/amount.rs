@@ -0,0 +1,37 @@
+//! Amounts of money, as typed in the Personnel and Materials panes. They're
+//! stored in cents.
+
+/// Parses an amount such as `12.50` into cents. Explains why it's rejected if
+/// it isn't a number, or is negative or too large to be stored.
+pub fn parse_amount(s: &str) -> Result<u16, String> {
+    let amount = s
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| format!("\"{s}\" isn't an amount: {e}"))?;
+    let cents = (amount * 100.).round();
+    if !(0. ..=u16::MAX as f64).contains(&cents) {
+        return Err(format!(
+            "Amounts go from 0 to {:.2}",
+            u16::MAX as f64 / 100.
+        ));
+    }
+    Ok(cents as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_amount;
+
+    #[test]
+    fn amounts_must_fit() {
+        assert_eq!(parse_amount(" 12.29"), Ok(1229));
+        assert_eq!(parse_amount("655.35"), Ok(u16::MAX));
+        assert_eq!(
+            parse_amount("655.36"),
+            Err("Amounts go from 0 to 655.35".to_owned())
+        );
+        assert!(parse_amount("-1").is_err());
+        assert!(parse_amount("NaN").is_err());
+        assert!(parse_amount("12,50").is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! The working time of the project: working days, working hours and holidays,
+//! and how durations in days and weeks are converted to working time.
+//!
+//! Every change goes through [`tasks_page::update`], which reschedules the
+//! tasks so that they keep their duration in working time.
+
+use std::collections::BTreeSet;
+
+use chrono::{NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::{TZ_VARIANTS, Tz};
+use iced::{
+    Alignment, Element,
+    widget::{Column, Row, button, checkbox, pick_list, row, text},
+};
+use once_cell::sync::Lazy;
+use planter_core::project::Project;
+use regex::bytes::Regex;
+
+use crate::{
+    calendar::{Calendar, WEEKDAYS},
+    date_input,
+    duration_format::{DurationFormat, DurationUnit},
+    extras::ProjectExtras,
+};
+
+use super::{
+    components::{data_cell::data_cell, data_label::data_label},
+    tasks_page::{self, TasksMessage, TasksState},
+};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+
+#[derive(Debug, Default)]
+pub struct CalendarState {
+    working_hours: String,
+    working_hours_err: Option<String>,
+    new_holiday: String,
+    new_holiday_err: Option<String>,
+    hours_per_day: String,
+    hours_per_day_err: Option<String>,
+    days_per_week: String,
+    days_per_week_err: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CalendarMessage {
+    ToggleWorkingDay(Weekday),
+    UpdateWorkingHours(String),
+    UpdateNewHoliday(String),
+    AddHoliday,
+    RemoveHoliday(NaiveDate),
+    /// Asks for an iCalendar file to read holidays from. The app opens the
+    /// file, and sends back [`CalendarMessage::AddHolidays`].
+    ImportHolidays,
+    AddHolidays(BTreeSet<NaiveDate>),
+    /// Changes the time zone of the project, which working hours and dates
+    /// are in.
+    SelectTimeZone(Tz),
+    SelectDurationUnit(DurationUnit),
+    UpdateHoursPerDay(String),
+    UpdateDaysPerWeek(String),
+}
+
+impl CalendarMessage {
+    /// Whether the message may change the project, rather than only what's
+    /// shown.
+    pub fn is_edit(&self) -> bool {
+        !matches!(
+            self,
+            CalendarMessage::UpdateNewHoliday(_) | CalendarMessage::ImportHolidays
+        )
+    }
+
+    /// Whether the message types into a field of the calendar.
+    pub fn is_typed(&self) -> bool {
+        matches!(
+            self,
+            CalendarMessage::UpdateWorkingHours(_)
+                | CalendarMessage::UpdateHoursPerDay(_)
+                | CalendarMessage::UpdateDaysPerWeek(_)
+        )
+    }
+}
+
+impl CalendarState {
+    pub fn from_extras(extras: &ProjectExtras) -> Self {
+        CalendarState {
+            working_hours: format_working_hours(extras.calendar()),
+            hours_per_day: DurationFormat::default()
+                .format(extras.duration_format().hours_per_day()),
+            days_per_week: extras.duration_format().days_per_week().to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn update(
+    state: &mut CalendarState,
+    tasks_state: &mut TasksState,
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    message: CalendarMessage,
+) {
+    let mut calendar = extras.calendar().clone();
+    let mut duration_format = *extras.duration_format();
+    let is_changed = match message {
+        // At least one day of the week must be worked.
+        CalendarMessage::ToggleWorkingDay(weekday) => calendar
+            .set_works_on(weekday, !calendar.works_on(weekday))
+            .is_ok(),
+        CalendarMessage::UpdateWorkingHours(h) => {
+            let changed = parse_working_hours(&h)
+                .ok_or_else(|| {
+                    format!(
+                        "\"{}\" isn't a list of periods, such as 08:00-12:00;13:00-17:00",
+                        h.trim()
+                    )
+                })
+                .and_then(|working_hours| {
+                    calendar
+                        .set_working_hours(working_hours)
+                        .map_err(|_| "Periods can't end before they start, or overlap".to_owned())
+                });
+            state.working_hours_err = changed.clone().err();
+            state.working_hours = h;
+            changed.is_ok()
+        }
+        CalendarMessage::UpdateNewHoliday(d) => {
+            state.new_holiday = d;
+            state.new_holiday_err = None;
+            false
+        }
+        CalendarMessage::AddHoliday => {
+            let date = date_input::parse(&state.new_holiday, Utc::now(), &calendar, |day| {
+                calendar.to_utc(day.and_time(NaiveTime::MIN))
+            })
+            .map(|date| calendar.to_local(date).date());
+            match date {
+                Some(date) => {
+                    calendar.add_holiday(date);
+                    state.new_holiday = "".to_owned();
+                    true
+                }
+                None => {
+                    state.new_holiday_err = Some(format!(
+                        "\"{}\" isn't a date, such as 2025-12-25 or 25 Dec 2025",
+                        state.new_holiday.trim()
+                    ));
+                    false
+                }
+            }
+        }
+        CalendarMessage::RemoveHoliday(date) => {
+            calendar.remove_holiday(date);
+            true
+        }
+        CalendarMessage::ImportHolidays => false,
+        CalendarMessage::AddHolidays(dates) => {
+            for date in dates {
+                calendar.add_holiday(date);
+            }
+            true
+        }
+        CalendarMessage::SelectTimeZone(time_zone) => {
+            tasks_page::update(
+                tasks_state,
+                project,
+                extras,
+                TasksMessage::UpdateTimeZone(time_zone),
+            );
+            false
+        }
+        CalendarMessage::SelectDurationUnit(unit) => {
+            duration_format.unit = unit;
+            false
+        }
+        // Hours are the unit here, whatever durations are shown in.
+        CalendarMessage::UpdateHoursPerDay(h) => {
+            state.hours_per_day_err = match DurationFormat::default().parse(&h) {
+                Some(hours_per_day) => duration_format
+                    .set_hours_per_day(hours_per_day)
+                    .err()
+                    .map(|_| "A day lasts from a minute to 24 hours".to_owned()),
+                None => Some(format!(
+                    "\"{}\" isn't a duration, such as 8h or 7h 30m",
+                    h.trim()
+                )),
+            };
+            state.hours_per_day = h;
+            false
+        }
+        CalendarMessage::UpdateDaysPerWeek(d) => {
+            state.days_per_week_err = d
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .is_none_or(|days_per_week| {
+                    duration_format.set_days_per_week(days_per_week).is_err()
+                })
+                .then(|| "A week has from 1 to 7 days".to_owned());
+            state.days_per_week = d;
+            false
+        }
+    };
+
+    if duration_format != *extras.duration_format() {
+        tasks_page::update(
+            tasks_state,
+            project,
+            extras,
+            TasksMessage::UpdateDurationFormat(duration_format),
+        );
+    }
+    if is_changed {
+        tasks_page::update(
+            tasks_state,
+            project,
+            extras,
+            TasksMessage::UpdateCalendar(calendar),
+        );
+    }
+}
+
+/// Parses working hours such as `08:00-12:00;13:00-17:00`. A period ending at
+/// `00:00` runs to the end of the day.
+fn parse_working_hours(s: &str) -> Option<Vec<(NaiveTime, NaiveTime)>> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^([0-9]{1,2}:[0-9]{2})\s*-\s*([0-9]{1,2}:[0-9]{2})$")
+            .expect("It wasn't possible to compile a hardcoded regex. This is a bug.")
+    });
+
+    s.split(';')
+        .map(|period_s| {
+            let captures = RE.captures(period_s.trim().as_bytes())?;
+            let time = |i: usize| {
+                let time_s = String::from_utf8_lossy(captures.get(i)?.as_bytes()).into_owned();
+                NaiveTime::parse_from_str(&time_s, TIME_FORMAT).ok()
+            };
+
+            Some((time(1)?, time(2)?))
+        })
+        .collect()
+}
+
+/// Formats working hours the way [`parse_working_hours`] reads them.
+fn format_working_hours(calendar: &Calendar) -> String {
+    calendar
+        .working_hours()
+        .iter()
+        .map(|(from, to)| format!("{}-{}", from.format(TIME_FORMAT), to.format(TIME_FORMAT)))
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+pub fn view<'a>(
+    state: &'a CalendarState,
+    extras: &'a ProjectExtras,
+) -> Element<'a, CalendarMessage> {
+    let calendar = extras.calendar();
+
+    let working_days = WEEKDAYS.into_iter().fold(Row::new(), |days, weekday| {
+        days.push(
+            checkbox(weekday.to_string(), calendar.works_on(weekday))
+                .on_toggle(move |_| CalendarMessage::ToggleWorkingDay(weekday)),
+        )
+    });
+
+    let holidays = calendar.holidays().map(|date| {
+        Row::new()
+            .push(data_label(date.format(DATE_FORMAT).to_string()))
+            .push(
+                button("Del")
+                    .on_press(CalendarMessage::RemoveHoliday(date))
+                    .width(100),
+            )
+            .into()
+    });
+
+    Column::new()
+        .push(text("Working days"))
+        .push(working_days.spacing(10))
+        .push(text("Working hours"))
+        .push(
+            row![
+                data_cell(
+                    "08:00-12:00;13:00-17:00",
+                    &state.working_hours,
+                    state.working_hours_err.as_deref(),
+                )
+                .on_input(CalendarMessage::UpdateWorkingHours)
+                .width(200),
+                text(format!(
+                    "{} h per day",
+                    calendar.hours_per_day().num_minutes() as f64 / 60.
+                ))
+                .size(14),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        )
+        .push(text("Time zone"))
+        .push(pick_list(
+            &TZ_VARIANTS[..],
+            Some(calendar.time_zone()),
+            CalendarMessage::SelectTimeZone,
+        ))
+        .push(text("Durations"))
+        .push(
+            row![
+                text("Shown in").size(14),
+                pick_list(
+                    DurationUnit::ALL,
+                    Some(extras.duration_format().unit),
+                    CalendarMessage::SelectDurationUnit,
+                ),
+                text("A day is").size(14),
+                data_cell(
+                    "8h",
+                    &state.hours_per_day,
+                    state.hours_per_day_err.as_deref()
+                )
+                .on_input(CalendarMessage::UpdateHoursPerDay)
+                .width(100),
+                text("A week is").size(14),
+                data_cell(
+                    "5",
+                    &state.days_per_week,
+                    state.days_per_week_err.as_deref()
+                )
+                .on_input(CalendarMessage::UpdateDaysPerWeek)
+                .width(100),
+                text("days").size(14),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        )
+        .push(text("Holidays"))
+        .extend(holidays)
+        .push(
+            Row::new()
+                .push(
+                    data_cell(
+                        "2025-12-25",
+                        &state.new_holiday,
+                        state.new_holiday_err.as_deref(),
+                    )
+                    .on_input(CalendarMessage::UpdateNewHoliday)
+                    .on_submit(CalendarMessage::AddHoliday),
+                )
+                .push(
+                    button("Add")
+                        .on_press(CalendarMessage::AddHoliday)
+                        .width(100),
+                )
+                .push(
+                    button("Import .ics")
+                        .on_press(CalendarMessage::ImportHolidays)
+                        .width(100),
+                ),
+        )
+        .spacing(5)
+        .padding(5)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::parse_working_hours;
+
+    #[test]
+    fn parse_working_hours_works() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+        assert_eq!(
+            parse_working_hours("08:00-12:00; 13:30 - 17:00"),
+            Some(vec![(time(8, 0), time(12, 0)), (time(13, 30), time(17, 0))])
+        );
+        assert_eq!(
+            parse_working_hours("22:00-00:00"),
+            Some(vec![(time(22, 0), time(0, 0))])
+        );
+        assert_eq!(parse_working_hours("8-12"), None);
+        assert_eq!(parse_working_hours("25:00-26:00"), None);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod data_cell;
 pub mod data_label;
+pub mod date_picker;
@@ -1,33 +1,132 @@
 use std::fmt::Display;
 
 use iced::{
-    Alignment,
-    widget::{TextInput, text_input},
+    Alignment, Element, Length, Padding,
+    widget::{TextInput, container, text, text_input, tooltip},
 };
 
 use crate::ui::constants::WIDTH;
 
+/// A cell of a grid. Cells with an error have a red border, and tell what's
+/// wrong with their value on hover.
+pub struct DataCell<'a, Message> {
+    input: TextInput<'a, Message>,
+    error: Option<&'a str>,
+}
+
+impl<'a, Message> DataCell<'a, Message>
+where
+    Message: 'a + Clone,
+{
+    pub fn on_input(self, on_input: impl Fn(String) -> Message + 'a) -> Self {
+        DataCell {
+            input: self.input.on_input(on_input),
+            ..self
+        }
+    }
+
+    pub fn on_input_maybe(self, on_input: Option<impl Fn(String) -> Message + 'a>) -> Self {
+        DataCell {
+            input: self.input.on_input_maybe(on_input),
+            ..self
+        }
+    }
+
+    pub fn on_submit(self, message: Message) -> Self {
+        DataCell {
+            input: self.input.on_submit(message),
+            ..self
+        }
+    }
+
+    pub fn padding(self, padding: impl Into<Padding>) -> Self {
+        DataCell {
+            input: self.input.padding(padding),
+            ..self
+        }
+    }
+
+    pub fn width(self, width: impl Into<Length>) -> Self {
+        DataCell {
+            input: self.input.width(width),
+            ..self
+        }
+    }
+}
+
+impl<'a, Message> From<DataCell<'a, Message>> for Element<'a, Message>
+where
+    Message: 'a + Clone,
+{
+    /// Valid cells are wrapped in an empty tooltip too, so that the widget
+    /// tree keeps its shape and the input keeps focus while a value is being
+    /// typed and turns valid or invalid.
+    fn from(cell: DataCell<'a, Message>) -> Self {
+        let is_error = cell.error.is_some();
+        let message = container(text(cell.error.unwrap_or_default()).size(14))
+            .padding(if is_error { 5 } else { 0 })
+            .style(move |theme| {
+                if is_error {
+                    container::rounded_box(theme)
+                } else {
+                    container::Style::default()
+                }
+            });
+
+        tooltip(cell.input, message, tooltip::Position::Bottom).into()
+    }
+}
+
 pub fn data_cell<'a, Message>(
     placeholder: impl Display,
     value: impl Display,
-    is_error: bool,
-) -> TextInput<'a, Message>
+    error: Option<&'a str>,
+) -> DataCell<'a, Message>
 where
     Message: 'a + Clone,
 {
-    text_input(&placeholder.to_string(), &value.to_string())
+    styled_cell(placeholder, value, error, false)
+}
+
+/// A [`data_cell`] with a thicker border, for rows on the critical path.
+pub fn critical_data_cell<'a, Message>(
+    placeholder: impl Display,
+    value: impl Display,
+    error: Option<&'a str>,
+) -> DataCell<'a, Message>
+where
+    Message: 'a + Clone,
+{
+    styled_cell(placeholder, value, error, true)
+}
+
+fn styled_cell<'a, Message>(
+    placeholder: impl Display,
+    value: impl Display,
+    error: Option<&'a str>,
+    is_critical: bool,
+) -> DataCell<'a, Message>
+where
+    Message: 'a + Clone,
+{
+    let is_error = error.is_some();
+    let input = text_input(&placeholder.to_string(), &value.to_string())
         .align_x(Alignment::Center)
         .style(move |theme: &iced::Theme, status| text_input::Style {
             border: iced::Border {
-                color: if !is_error {
-                    theme.palette().primary
-                } else {
+                color: if is_error {
                     theme.extended_palette().danger.base.color
+                } else if is_critical {
+                    theme.extended_palette().warning.base.color
+                } else {
+                    theme.palette().primary
                 },
                 radius: 0.0.into(),
-                width: 1.0,
+                width: if is_critical { 2.0 } else { 1.0 },
             },
             ..text_input::default(theme, status)
         })
-        .width(WIDTH)
+        .width(WIDTH);
+
+    DataCell { input, error }
 }
@@ -0,0 +1,74 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use iced::{
+    Alignment, Element, Length,
+    alignment::Horizontal,
+    widget::{Column, Row, button, row, text},
+};
+
+const DAY_WIDTH: u32 = 36;
+const WEEKDAY_NAMES: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// A month to pick a day from, with buttons to show the previous and next
+/// months. Any day of `month` stands for it.
+pub fn date_picker<'a, Message>(
+    month: NaiveDate,
+    selected: Option<NaiveDate>,
+    on_pick: impl Fn(NaiveDate) -> Message,
+    on_show_month: impl Fn(NaiveDate) -> Message,
+) -> Element<'a, Message>
+where
+    Message: 'a + Clone,
+{
+    let first = month.with_day(1).unwrap_or(month);
+    let header = row![
+        first
+            .checked_sub_months(Months::new(1))
+            .map(|previous| button("<").on_press(on_show_month(previous))),
+        text(first.format("%B %Y").to_string())
+            .width(DAY_WIDTH * 5)
+            .align_x(Horizontal::Center),
+        first
+            .checked_add_months(Months::new(1))
+            .map(|next| button(">").on_press(on_show_month(next))),
+    ]
+    .align_y(Alignment::Center);
+
+    let weekdays = WEEKDAY_NAMES
+        .into_iter()
+        .fold(Row::new(), |weekdays, name| {
+            weekdays.push(text(name).width(DAY_WIDTH).align_x(Horizontal::Center))
+        });
+
+    // Six weeks from the Monday before the first of the month fit any month.
+    let monday = first - Days::new(first.weekday().num_days_from_monday() as u64);
+    let weeks = (0..6).map(|week| {
+        (0..7)
+            .filter_map(|day| monday.checked_add_days(Days::new(week * 7 + day)))
+            .fold(Row::new(), |days, day| {
+                days.push(
+                    button(
+                        text(day.day())
+                            .width(Length::Fill)
+                            .align_x(Horizontal::Center),
+                    )
+                    .style(if selected == Some(day) {
+                        button::primary
+                    } else if day.month() == first.month() {
+                        button::text
+                    } else {
+                        button::secondary
+                    })
+                    .on_press(on_pick(day))
+                    .width(DAY_WIDTH),
+                )
+            })
+            .into()
+    });
+
+    Column::new()
+        .push(header)
+        .push(weekdays)
+        .extend(weeks)
+        .spacing(2)
+        .into()
+}
@@ -0,0 +1,177 @@
+use std::fmt::Display;
+
+use iced::{
+    Element,
+    widget::{Column, Row, button, pick_list, row, text},
+};
+
+use crate::{
+    io::csv::CsvTable,
+    ui::{constants, materials_page, personnel_page, tasks_page},
+};
+
+use super::components::data_label::data_label;
+
+/// Number of rows shown in the preview.
+const PREVIEW_ROWS: usize = 5;
+
+/// Data grids that can be exported to and imported from CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvGrid {
+    Tasks,
+    Personnel,
+    Materials,
+}
+
+impl CsvGrid {
+    pub fn fields(&self) -> &'static [&'static str] {
+        match self {
+            CsvGrid::Tasks => &tasks_page::CSV_FIELDS,
+            CsvGrid::Personnel => &personnel_page::CSV_FIELDS,
+            CsvGrid::Materials => &materials_page::CSV_FIELDS,
+        }
+    }
+}
+
+/// A CSV file waiting for its columns to be mapped to the fields of a grid.
+#[derive(Debug)]
+pub struct CsvImportState {
+    pub grid: CsvGrid,
+    table: CsvTable,
+    /// For every field of the grid, the column it's read from, if any.
+    mapping: Vec<Option<usize>>,
+}
+
+/// A column of the CSV file, or no column at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnChoice {
+    index: Option<usize>,
+    header: String,
+}
+
+impl Display for ColumnChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.index {
+            Some(_) => f.write_str(&self.header),
+            None => f.write_str("(skip)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CsvImportMessage {
+    MapField(usize, ColumnChoice),
+    Confirm,
+    Cancel,
+}
+
+impl CsvImportState {
+    /// Fields are initially mapped to the columns with the same header, if any.
+    pub fn new(grid: CsvGrid, table: CsvTable) -> Self {
+        let mapping = grid
+            .fields()
+            .iter()
+            .map(|field| {
+                table
+                    .headers
+                    .iter()
+                    .position(|header| header.eq_ignore_ascii_case(field))
+            })
+            .collect();
+
+        CsvImportState {
+            grid,
+            table,
+            mapping,
+        }
+    }
+
+    /// Every row of the file, with one value per field of the grid.
+    pub fn mapped_rows(&self) -> Vec<Vec<Option<String>>> {
+        self.table
+            .rows
+            .iter()
+            .map(|row| {
+                self.mapping
+                    .iter()
+                    .map(|column| column.and_then(|c| row.get(c).cloned()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn column_choices(&self) -> Vec<ColumnChoice> {
+        std::iter::once(ColumnChoice {
+            index: None,
+            header: "".to_owned(),
+        })
+        .chain(
+            self.table
+                .headers
+                .iter()
+                .enumerate()
+                .map(|(i, header)| ColumnChoice {
+                    index: Some(i),
+                    header: header.clone(),
+                }),
+        )
+        .collect()
+    }
+}
+
+pub fn update(state: &mut CsvImportState, message: CsvImportMessage) {
+    match message {
+        CsvImportMessage::MapField(field, column) => state.mapping[field] = column.index,
+        CsvImportMessage::Confirm | CsvImportMessage::Cancel => {}
+    }
+}
+
+pub fn view(state: &CsvImportState) -> Element<'_, CsvImportMessage> {
+    let choices = state.column_choices();
+
+    let headers = Row::new().extend(state.grid.fields().iter().map(|f| data_label(*f).into()));
+    let mapping = Row::new().extend(state.mapping.iter().enumerate().map(|(field, column)| {
+        let selected = choices
+            .iter()
+            .find(|choice| choice.index == *column)
+            .cloned();
+        pick_list(choices.clone(), selected, move |c| {
+            CsvImportMessage::MapField(field, c)
+        })
+        .width(constants::WIDTH)
+        .into()
+    }));
+    let preview = state
+        .mapped_rows()
+        .into_iter()
+        .take(PREVIEW_ROWS)
+        .map(|row| {
+            Row::new()
+                .extend(
+                    row.into_iter()
+                        .map(|value| data_label(value.unwrap_or_default()).into()),
+                )
+                .into()
+        });
+
+    Column::new()
+        .push(text(format!(
+            "Importing {} rows. Choose the column for each field:",
+            state.table.rows.len()
+        )))
+        .push(headers)
+        .push(mapping)
+        .extend(preview)
+        .push(
+            row![
+                button("Import").on_press(CsvImportMessage::Confirm),
+                button("Cancel")
+                    .style(button::secondary)
+                    .on_press(CsvImportMessage::Cancel),
+            ]
+            .spacing(5),
+        )
+        .spacing(5)
+        .padding(5)
+        .into()
+}
@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+
+use rfd::AsyncFileDialog;
+
+/// Asks the user for a file to open. Returns `None` if the dialog was dismissed.
+pub async fn pick_file(filter_name: &str, extensions: &[&str]) -> Option<PathBuf> {
+    AsyncFileDialog::new()
+        .add_filter(filter_name, extensions)
+        .pick_file()
+        .await
+        .map(|handle| handle.path().to_owned())
+}
+
+/// Asks the user where to save a file. Returns `None` if the dialog was dismissed.
+pub async fn pick_save_path(filter_name: &str, extensions: &[&str]) -> Option<PathBuf> {
+    AsyncFileDialog::new()
+        .add_filter(filter_name, extensions)
+        .save_file()
+        .await
+        .map(|handle| handle.path().to_owned())
+}
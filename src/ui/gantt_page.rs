@@ -0,0 +1,787 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, TimeDelta, Timelike, Utc};
+use iced::{
+    Alignment, Color, Element, Pixels, Point, Rectangle, Renderer, Size, Theme, mouse,
+    widget::{
+        Canvas, Column,
+        canvas::{self, Frame, Geometry, Path, Stroke, Text},
+        pick_list, row, scrollable, text,
+    },
+};
+use planter_core::project::Project;
+
+use crate::{
+    calendar::Calendar,
+    extras::ProjectExtras,
+    scheduling::{self, Link},
+};
+
+use super::tasks_page::{self, TasksMessage, TasksState};
+
+const HEADER_HEIGHT: f32 = 30.;
+const ROW_HEIGHT: f32 = 30.;
+const BAR_HEIGHT: f32 = 16.;
+/// Room left after the last tick for the name of the last tasks.
+const LABEL_MARGIN: f32 = 200.;
+const TEXT_SIZE: f32 = 12.;
+/// Distance from the end of a bar within which a drag changes the duration.
+const HANDLE_WIDTH: f32 = 5.;
+/// Width of the ends of the brackets of summary tasks.
+const SUMMARY_END_WIDTH: f32 = 4.;
+/// Half the width of the diamonds of milestones.
+const MILESTONE_RADIUS: f32 = BAR_HEIGHT / 2.;
+/// Width of the chart drawn before it's been scrolled, wider than any screen.
+const INITIAL_VIEW_WIDTH: f32 = 4000.;
+
+#[derive(Debug, Default)]
+pub struct GanttState {
+    zoom: Zoom,
+    /// Horizontal offset and width of the part of the chart in view, once the
+    /// chart has been scrolled.
+    view: Option<(f32, f32)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum GanttMessage {
+    ZoomSelected(Zoom),
+    Scrolled(scrollable::Viewport),
+    MoveTask(usize, DateTime<Utc>),
+    ResizeTask(usize, DateTime<Utc>),
+    LinkTasks {
+        predecessor: usize,
+        successor: usize,
+    },
+}
+
+impl GanttMessage {
+    /// Whether the message may change the project, rather than only what's
+    /// shown.
+    pub fn is_edit(&self) -> bool {
+        !matches!(
+            self,
+            GanttMessage::ZoomSelected(_) | GanttMessage::Scrolled(_)
+        )
+    }
+}
+
+/// Drag in progress on the chart. Points are in chart coordinates.
+#[derive(Debug, Default)]
+enum Interaction {
+    #[default]
+    Idle,
+    Moving {
+        task: usize,
+        from: Point,
+        to: Point,
+    },
+    Resizing {
+        task: usize,
+        to: Point,
+    },
+    Linking {
+        task: usize,
+        to: Point,
+    },
+}
+
+/// Unit of the timescale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Zoom {
+    Hours,
+    #[default]
+    Days,
+    Weeks,
+    Months,
+}
+
+impl Zoom {
+    const ALL: [Zoom; 4] = [Zoom::Hours, Zoom::Days, Zoom::Weeks, Zoom::Months];
+
+    fn pixels_per_hour(&self) -> f32 {
+        match self {
+            Zoom::Hours => 40.,
+            Zoom::Days => 40. / 24.,
+            Zoom::Weeks => 80. / (24. * 7.),
+            Zoom::Months => 120. / (24. * 30.),
+        }
+    }
+
+    /// Returns the start of the tick the date falls in. Ticks follow the
+    /// clocks of the time zone of the project.
+    fn floor(&self, date: DateTime<Utc>, calendar: &Calendar) -> DateTime<Utc> {
+        let local = calendar.to_local(date);
+        let day = local.date();
+        let floor = match self {
+            Zoom::Hours => day.and_hms_opt(local.hour(), 0, 0).unwrap(),
+            Zoom::Days => day.and_time(NaiveTime::MIN),
+            Zoom::Weeks => (day - TimeDelta::days(day.weekday().num_days_from_monday() as i64))
+                .and_time(NaiveTime::MIN),
+            Zoom::Months => NaiveDate::from_ymd_opt(day.year(), day.month(), 1)
+                .unwrap()
+                .and_time(NaiveTime::MIN),
+        };
+
+        calendar.to_utc(floor)
+    }
+
+    fn next_tick(&self, tick: DateTime<Utc>, calendar: &Calendar) -> DateTime<Utc> {
+        let local = calendar.to_local(tick);
+        match self {
+            Zoom::Hours => tick + TimeDelta::hours(1),
+            Zoom::Days => calendar.to_utc(local + TimeDelta::days(1)),
+            Zoom::Weeks => calendar.to_utc(local + TimeDelta::weeks(1)),
+            Zoom::Months => calendar.to_utc(local + Months::new(1)),
+        }
+    }
+
+    /// Rounds a drag to the precision that makes sense at this zoom level.
+    fn snap(&self, delta: TimeDelta) -> TimeDelta {
+        let step = match self {
+            Zoom::Hours => 15,
+            Zoom::Days => 60,
+            Zoom::Weeks | Zoom::Months => 60 * 24,
+        };
+
+        TimeDelta::minutes((delta.num_minutes() as f64 / step as f64).round() as i64 * step)
+    }
+
+    fn label(&self, tick: DateTime<Utc>, calendar: &Calendar) -> String {
+        let tick = calendar.to_local(tick);
+        match self {
+            Zoom::Hours => tick.format("%H:%M").to_string(),
+            Zoom::Days => tick.format("%d %b").to_string(),
+            Zoom::Weeks => tick.format("W%V %Y").to_string(),
+            Zoom::Months => tick.format("%b %Y").to_string(),
+        }
+    }
+}
+
+impl Display for Zoom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Zoom::Hours => f.write_str("Hours"),
+            Zoom::Days => f.write_str("Days"),
+            Zoom::Weeks => f.write_str("Weeks"),
+            Zoom::Months => f.write_str("Months"),
+        }
+    }
+}
+
+/// Edits made on the chart are applied through [`tasks_page::update`], so that
+/// they're validated like the ones made in the grid and the two stay in sync.
+pub fn update(
+    state: &mut GanttState,
+    tasks_state: &mut TasksState,
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    message: GanttMessage,
+) {
+    match message {
+        GanttMessage::ZoomSelected(zoom) => state.zoom = zoom,
+        GanttMessage::Scrolled(viewport) => {
+            state.view = Some((viewport.absolute_offset().x, viewport.bounds().width));
+        }
+        GanttMessage::MoveTask(i, start) => {
+            tasks_page::update(
+                tasks_state,
+                project,
+                extras,
+                TasksMessage::MoveTask(i, start),
+            );
+        }
+        GanttMessage::ResizeTask(i, finish) => {
+            tasks_page::update(
+                tasks_state,
+                project,
+                extras,
+                TasksMessage::ResizeTask(i, finish),
+            );
+        }
+        GanttMessage::LinkTasks {
+            predecessor,
+            successor,
+        } => {
+            let predecessors = project
+                .predecessors_indices(successor)
+                .collect::<Vec<usize>>();
+            if predecessors.contains(&predecessor) {
+                return;
+            }
+
+            // Existing links keep their type and lag, the new one is a plain
+            // finish-to-start. Links are typed with task IDs.
+            let ids = extras.task_ids();
+            let links = tasks_page::format_links(
+                predecessors
+                    .into_iter()
+                    .map(|p| (ids.id(p), extras.link(p, successor)))
+                    .chain(std::iter::once((ids.id(predecessor), Link::default()))),
+                extras.duration_format(),
+            );
+            tasks_page::update(
+                tasks_state,
+                project,
+                extras,
+                TasksMessage::UpdatePredecessors(successor, links),
+            );
+        }
+    }
+}
+
+pub fn view<'a>(
+    state: &'a GanttState,
+    project: &'a Project,
+    extras: &'a ProjectExtras,
+) -> Element<'a, GanttMessage> {
+    let chart = Chart::new(project, extras, state.zoom, Utc::now(), state.view);
+    let size = chart.size();
+
+    Column::new()
+        .push(
+            row![
+                text("Zoom"),
+                pick_list(Zoom::ALL, Some(state.zoom), GanttMessage::ZoomSelected),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center),
+        )
+        .push(
+            text("Drag a bar to move it, its end to change its duration, or onto another bar to link them.")
+                .size(TEXT_SIZE),
+        )
+        .push(
+            scrollable(Canvas::new(chart).width(size.width).height(size.height))
+                .direction(scrollable::Direction::Horizontal(
+                    scrollable::Scrollbar::default(),
+                ))
+                .on_scroll(GanttMessage::Scrolled),
+        )
+        .spacing(5)
+        .into()
+}
+
+struct Chart<'a> {
+    project: &'a Project,
+    extras: &'a ProjectExtras,
+    zoom: Zoom,
+    today: DateTime<Utc>,
+    /// First tick of the timescale.
+    first: DateTime<Utc>,
+    /// Last tick of the timescale.
+    last: DateTime<Utc>,
+    /// Horizontal bounds of the part of the chart in view. Only the
+    /// timescale there is drawn, since at fine zoom levels schedules spanning
+    /// years have far too many ticks.
+    view: (f32, f32),
+    /// Whether each task is on the critical path.
+    critical: Vec<bool>,
+    /// Tasks in the order of the rows, which is the outline order.
+    rows: Vec<usize>,
+    /// Row of each task.
+    row_of: Vec<usize>,
+    /// Whether each task is a summary task.
+    summaries: Vec<bool>,
+    /// Whether each task is a milestone.
+    milestones: Vec<bool>,
+}
+
+impl<'a> Chart<'a> {
+    fn new(
+        project: &'a Project,
+        extras: &'a ProjectExtras,
+        zoom: Zoom,
+        today: DateTime<Utc>,
+        view: Option<(f32, f32)>,
+    ) -> Self {
+        let (first, last) = project
+            .tasks()
+            .flat_map(|task| [task.start(), task.finish()])
+            .flatten()
+            .fold(
+                None,
+                |range: Option<(DateTime<Utc>, DateTime<Utc>)>, date| {
+                    Some(match range {
+                        Some((first, last)) => (first.min(date), last.max(date)),
+                        None => (date, date),
+                    })
+                },
+            )
+            .unwrap_or((today, today));
+
+        let task_count = project.tasks().count();
+        let rows = extras
+            .outline(task_count)
+            .into_iter()
+            .map(|entry| entry.task)
+            .collect::<Vec<usize>>();
+        let mut row_of = vec![0; task_count];
+        for (row, &task) in rows.iter().enumerate() {
+            row_of[task] = row;
+        }
+
+        Chart {
+            project,
+            extras,
+            zoom,
+            today,
+            first: zoom.floor(first, extras.calendar()),
+            last: zoom.next_tick(zoom.floor(last, extras.calendar()), extras.calendar()),
+            view: view.map_or((0., INITIAL_VIEW_WIDTH), |(x, width)| (x, x + width)),
+            critical: scheduling::schedule(project, extras)
+                .iter()
+                .map(|s| s.is_some_and(|s| s.is_critical()))
+                .collect(),
+            rows,
+            row_of,
+            summaries: (0..task_count)
+                .map(|i| extras.is_summary(i, task_count))
+                .collect(),
+            milestones: (0..task_count)
+                .map(|i| extras.is_milestone(i) && !extras.is_summary(i, task_count))
+                .collect(),
+        }
+    }
+
+    fn x(&self, date: DateTime<Utc>) -> f32 {
+        (date - self.first).num_minutes() as f32 / 60. * self.zoom.pixels_per_hour()
+    }
+
+    /// The date at a horizontal position, within the timescale.
+    fn date_at(&self, x: f32) -> DateTime<Utc> {
+        let date = self.first + TimeDelta::minutes((x / self.zoom.pixels_per_hour() * 60.) as i64);
+        date.clamp(self.first, self.last)
+    }
+
+    /// Time spanned by a horizontal distance, snapped to the zoom level.
+    fn time_delta(&self, dx: f32) -> TimeDelta {
+        self.zoom.snap(TimeDelta::minutes(
+            (dx / self.zoom.pixels_per_hour() * 60.) as i64,
+        ))
+    }
+
+    /// Returns the task on the row at the given height.
+    fn row_at(&self, y: f32) -> Option<usize> {
+        let row = ((y - HEADER_HEIGHT) / ROW_HEIGHT).floor();
+        (row >= 0.)
+            .then_some(row as usize)
+            .and_then(|row| self.rows.get(row).copied())
+    }
+
+    /// Start and finish of a task, if it has both. Milestones finish when they
+    /// start.
+    fn dates(&self, task_index: usize) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let task = self.project.task(task_index)?;
+        let start = task.start()?;
+        if self.milestones[task_index] {
+            return Some((start, start));
+        }
+        Some((start, task.finish()?))
+    }
+
+    /// Horizontal bounds of the bar of a task, or of the diamond of a
+    /// milestone, if it has one.
+    fn bar(&self, task_index: usize) -> Option<(f32, f32)> {
+        let (start, finish) = self.dates(task_index)?;
+        if self.milestones[task_index] {
+            let x = self.x(start);
+            return Some((x - MILESTONE_RADIUS, x + MILESTONE_RADIUS));
+        }
+        Some((self.x(start), self.x(finish)))
+    }
+
+    /// Returns the task whose bar is under the point.
+    fn task_at(&self, point: Point) -> Option<usize> {
+        let task = self.row_at(point.y)?;
+        let (x0, x1) = self.bar(task)?;
+        (x0 - HANDLE_WIDTH..=x1 + HANDLE_WIDTH)
+            .contains(&point.x)
+            .then_some(task)
+    }
+
+    /// Summary tasks have no handle, since their finish is their children's,
+    /// and neither have milestones, since they have no duration.
+    fn is_on_handle(&self, task_index: usize, point: Point) -> bool {
+        !self.summaries[task_index]
+            && !self.milestones[task_index]
+            && self
+                .bar(task_index)
+                .is_some_and(|(_, x1)| (point.x - x1).abs() <= HANDLE_WIDTH)
+    }
+
+    /// Vertical middle of the row of a task.
+    fn y(&self, task_index: usize) -> f32 {
+        HEADER_HEIGHT + (self.row_of[task_index] as f32 + 0.5) * ROW_HEIGHT
+    }
+
+    fn size(&self) -> Size {
+        Size::new(
+            self.x(self.last) + LABEL_MARGIN,
+            HEADER_HEIGHT + self.project.tasks().count() as f32 * ROW_HEIGHT,
+        )
+    }
+
+    fn draw_timescale(&self, frame: &mut Frame, theme: &Theme) {
+        let palette = theme.extended_palette();
+        let calendar = self.extras.calendar();
+        let mut tick = self.zoom.floor(self.date_at(self.view.0), calendar);
+        let last = self.date_at(self.view.1);
+
+        while tick <= last {
+            let x = self.x(tick);
+            frame.stroke(
+                &Path::line(Point::new(x, 0.), Point::new(x, frame.height())),
+                Stroke::default()
+                    .with_color(palette.background.strong.color)
+                    .with_width(1.),
+            );
+            frame.fill_text(Text {
+                content: self.zoom.label(tick, calendar),
+                position: Point::new(x + 3., (HEADER_HEIGHT - TEXT_SIZE) / 2.),
+                color: palette.background.base.text,
+                size: Pixels(TEXT_SIZE),
+                ..Text::default()
+            });
+            tick = self.zoom.next_tick(tick, calendar);
+        }
+    }
+
+    /// Shades the days off of the project calendar, when days are wide enough
+    /// to be seen.
+    fn draw_non_working_days(&self, frame: &mut Frame, color: Color) {
+        if !matches!(self.zoom, Zoom::Hours | Zoom::Days) {
+            return;
+        }
+
+        let calendar = self.extras.calendar();
+        let width = 24. * self.zoom.pixels_per_hour();
+        let last_day = calendar.to_local(self.date_at(self.view.1)).date();
+        for day in calendar
+            .to_local(self.date_at(self.view.0))
+            .date()
+            .iter_days()
+            .take_while(|day| *day <= last_day)
+            .filter(|&day| !calendar.is_working_day(day))
+        {
+            frame.fill_rectangle(
+                Point::new(
+                    self.x(calendar.to_utc(day.and_time(NaiveTime::MIN))),
+                    HEADER_HEIGHT,
+                ),
+                Size::new(width, frame.height() - HEADER_HEIGHT),
+                color,
+            );
+        }
+    }
+
+    fn draw_links(&self, frame: &mut Frame, color: Color) {
+        for successor in 0..self.project.tasks().count() {
+            for predecessor in self.project.predecessors_indices(successor) {
+                let link = self.extras.link(predecessor, successor);
+                let (Some(predecessor_dates), Some(successor_dates)) =
+                    (self.dates(predecessor), self.dates(successor))
+                else {
+                    continue;
+                };
+                let from = if link.kind.is_from_start() {
+                    predecessor_dates.0
+                } else {
+                    predecessor_dates.1
+                };
+                let to = if link.kind.is_to_finish() {
+                    successor_dates.1
+                } else {
+                    successor_dates.0
+                };
+                draw_arrow(
+                    frame,
+                    Point::new(self.x(from), self.y(predecessor)),
+                    Point::new(self.x(to), self.y(successor)),
+                    color,
+                );
+            }
+        }
+    }
+
+    fn draw_bars(&self, frame: &mut Frame, theme: &Theme) {
+        let palette = theme.extended_palette();
+
+        for (i, task) in self.project.tasks().enumerate() {
+            let y = self.y(i);
+            let mut label_x = 5.;
+
+            if let (Some(start), Some(finish)) = (task.start(), task.finish())
+                && self.summaries[i]
+            {
+                // A bracket spanning the children.
+                let (x0, x1) = (self.x(start), self.x(finish));
+                let color = palette.background.base.text;
+                let top = y - BAR_HEIGHT / 2.;
+                frame.fill_rectangle(
+                    Point::new(x0, top),
+                    Size::new((x1 - x0).max(2.), BAR_HEIGHT / 3.),
+                    color,
+                );
+                for x in [x0, x1 - SUMMARY_END_WIDTH] {
+                    frame.fill_rectangle(
+                        Point::new(x, top),
+                        Size::new(SUMMARY_END_WIDTH, BAR_HEIGHT * 2. / 3.),
+                        color,
+                    );
+                }
+                label_x = x1 + 5.;
+            } else if let Some(start) = task.start()
+                && self.milestones[i]
+            {
+                let x = self.x(start);
+                let diamond = Path::new(|b| {
+                    b.move_to(Point::new(x, y - MILESTONE_RADIUS));
+                    b.line_to(Point::new(x + MILESTONE_RADIUS, y));
+                    b.line_to(Point::new(x, y + MILESTONE_RADIUS));
+                    b.line_to(Point::new(x - MILESTONE_RADIUS, y));
+                    b.close();
+                });
+                frame.fill(
+                    &diamond,
+                    if task.completed() {
+                        palette.success.base.color
+                    } else if self.critical[i] {
+                        palette.danger.base.color
+                    } else {
+                        palette.background.base.text
+                    },
+                );
+                label_x = x + MILESTONE_RADIUS + 5.;
+            } else if let (Some(start), Some(finish)) = (task.start(), task.finish()) {
+                let (x0, x1) = (self.x(start), self.x(finish));
+                let width = (x1 - x0).max(2.);
+                frame.fill_rectangle(
+                    Point::new(x0, y - BAR_HEIGHT / 2.),
+                    Size::new(width, BAR_HEIGHT),
+                    if self.critical[i] {
+                        palette.danger.base.color
+                    } else {
+                        palette.primary.base.color
+                    },
+                );
+                // The part of the bar already done.
+                let percent_complete = self.extras.progress(i).percent_complete;
+                if percent_complete > 0 {
+                    frame.fill_rectangle(
+                        Point::new(x0, y - BAR_HEIGHT / 2.),
+                        Size::new(width * percent_complete as f32 / 100., BAR_HEIGHT),
+                        palette.success.base.color,
+                    );
+                }
+                label_x = x1 + 5.;
+            }
+
+            let percent_complete = self.extras.progress(i).percent_complete;
+            frame.fill_text(Text {
+                content: match percent_complete {
+                    100 => format!("✓ {}", task.name()),
+                    0 => task.name().to_owned(),
+                    _ => format!("{} {percent_complete}%", task.name()),
+                },
+                position: Point::new(label_x, y - TEXT_SIZE / 2.),
+                color: palette.background.base.text,
+                size: Pixels(TEXT_SIZE),
+                ..Text::default()
+            });
+        }
+    }
+
+    fn draw_today(&self, frame: &mut Frame, color: Color) {
+        if self.today < self.first || self.today > self.last {
+            return;
+        }
+
+        let x = self.x(self.today);
+        frame.stroke(
+            &Path::line(Point::new(x, 0.), Point::new(x, frame.height())),
+            Stroke::default().with_color(color).with_width(2.),
+        );
+    }
+
+    /// Draws the outcome of the drag in progress.
+    fn draw_interaction(&self, frame: &mut Frame, interaction: &Interaction, color: Color) {
+        let ghost = color.scale_alpha(0.5);
+
+        match *interaction {
+            Interaction::Idle => {}
+            Interaction::Moving { task, from, to } => {
+                if let Some((x0, x1)) = self.bar(task) {
+                    let y = self.y(task);
+                    frame.fill_rectangle(
+                        Point::new(x0 + to.x - from.x, y - BAR_HEIGHT / 2.),
+                        Size::new((x1 - x0).max(2.), BAR_HEIGHT),
+                        ghost,
+                    );
+                }
+            }
+            Interaction::Resizing { task, to } => {
+                if let Some((x0, _)) = self.bar(task) {
+                    let y = self.y(task);
+                    frame.fill_rectangle(
+                        Point::new(x0, y - BAR_HEIGHT / 2.),
+                        Size::new((to.x - x0).max(2.), BAR_HEIGHT),
+                        ghost,
+                    );
+                }
+            }
+            Interaction::Linking { task, to } => {
+                if let Some((_, x1)) = self.bar(task) {
+                    draw_arrow(frame, Point::new(x1, self.y(task)), to, color);
+                }
+            }
+        }
+    }
+}
+
+impl canvas::Program<GanttMessage> for Chart<'_> {
+    type State = Interaction;
+
+    fn update(
+        &self,
+        interaction: &mut Interaction,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<GanttMessage>> {
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_in(bounds)?;
+                let task = self.task_at(position)?;
+                *interaction = if self.is_on_handle(task, position) {
+                    Interaction::Resizing { task, to: position }
+                } else {
+                    Interaction::Moving {
+                        task,
+                        from: position,
+                        to: position,
+                    }
+                };
+
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            canvas::Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let position = cursor.position_in(bounds)?;
+                match interaction {
+                    Interaction::Idle => return None,
+                    // Leaving the row of the task turns the move into a link.
+                    Interaction::Moving { task, .. } if self.row_at(position.y) != Some(*task) => {
+                        *interaction = Interaction::Linking {
+                            task: *task,
+                            to: position,
+                        };
+                    }
+                    Interaction::Moving { to, .. }
+                    | Interaction::Resizing { to, .. }
+                    | Interaction::Linking { to, .. } => *to = position,
+                }
+
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let message = match std::mem::take(interaction) {
+                    Interaction::Idle => return None,
+                    Interaction::Moving { task, from, to } => {
+                        let start = self.project.task(task).and_then(|t| t.start());
+                        let delta = self.time_delta(to.x - from.x);
+                        start
+                            .filter(|_| !delta.is_zero())
+                            .map(|start| GanttMessage::MoveTask(task, start + delta))
+                    }
+                    Interaction::Resizing { task, to } => {
+                        let finish = self.project.task(task).and_then(|t| t.finish());
+                        finish.and_then(|finish| {
+                            let delta = self.time_delta(to.x - self.x(finish));
+                            (!delta.is_zero())
+                                .then_some(GanttMessage::ResizeTask(task, finish + delta))
+                        })
+                    }
+                    Interaction::Linking { task, to } => self
+                        .task_at(to)
+                        .filter(|successor| *successor != task)
+                        .map(|successor| GanttMessage::LinkTasks {
+                            predecessor: task,
+                            successor,
+                        }),
+                };
+
+                Some(match message {
+                    Some(message) => canvas::Action::publish(message).and_capture(),
+                    None => canvas::Action::request_redraw(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        interaction: &Interaction,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        self.draw_non_working_days(&mut frame, palette.background.weak.color);
+        self.draw_timescale(&mut frame, theme);
+        self.draw_links(&mut frame, palette.secondary.strong.color);
+        self.draw_bars(&mut frame, theme);
+        self.draw_today(&mut frame, palette.danger.base.color);
+        self.draw_interaction(&mut frame, interaction, palette.primary.strong.color);
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        interaction: &Interaction,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match interaction {
+            Interaction::Moving { .. } | Interaction::Linking { .. } => {
+                mouse::Interaction::Grabbing
+            }
+            Interaction::Resizing { .. } => mouse::Interaction::ResizingHorizontally,
+            Interaction::Idle => {
+                let Some(position) = cursor.position_in(bounds) else {
+                    return mouse::Interaction::default();
+                };
+                match self.task_at(position) {
+                    Some(task) if self.is_on_handle(task, position) => {
+                        mouse::Interaction::ResizingHorizontally
+                    }
+                    Some(_) => mouse::Interaction::Grab,
+                    None => mouse::Interaction::default(),
+                }
+            }
+        }
+    }
+}
+
+/// Draws an elbow arrow from the finish of a predecessor to the start of a successor.
+fn draw_arrow(frame: &mut Frame, from: Point, to: Point, color: Color) {
+    let elbow_x = from.x + 6.;
+    let line = Path::new(|b| {
+        b.move_to(from);
+        b.line_to(Point::new(elbow_x, from.y));
+        b.line_to(Point::new(elbow_x, to.y));
+        b.line_to(to);
+    });
+    frame.stroke(&line, Stroke::default().with_color(color).with_width(1.));
+
+    let head = Path::new(|b| {
+        b.move_to(Point::new(to.x - 5., to.y - 4.));
+        b.line_to(to);
+        b.line_to(Point::new(to.x - 5., to.y + 4.));
+        b.close();
+    });
+    frame.fill(&head, color);
+}
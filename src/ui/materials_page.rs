@@ -9,7 +9,12 @@ use planter_core::{
     resources::{Material, NonConsumable, Resource},
 };
 
-use crate::{AppMessage, ui::constants};
+use crate::{
+    AppMessage,
+    extras::ProjectExtras,
+    io::csv::CsvTable,
+    ui::{amount::parse_amount, constants},
+};
 
 use super::components::{data_cell::data_cell, data_label::data_label};
 
@@ -17,7 +22,7 @@ use super::components::{data_cell::data_cell, data_label::data_label};
 pub struct MaterialsState {
     repr: Vec<Repr>,
     new_material_name: String,
-    is_new_material_err: bool,
+    new_material_err: Option<String>,
 }
 
 #[derive(Debug)]
@@ -37,9 +42,9 @@ struct ConsumableRepr {
     res_id: usize,
     name: String,
     quantity: String,
-    is_quantity_err: bool,
+    quantity_err: Option<String>,
     cost_per_unit: String,
-    is_cost_err: bool,
+    cost_err: Option<String>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -47,9 +52,9 @@ struct NonConsumableRepr {
     res_id: usize,
     name: String,
     quantity: String,
-    is_quantity_err: bool,
+    quantity_err: Option<String>,
     hourly_rate: String,
-    is_rate_err: bool,
+    rate_err: Option<String>,
 }
 
 impl From<ConsumableRepr> for NonConsumableRepr {
@@ -58,9 +63,9 @@ impl From<ConsumableRepr> for NonConsumableRepr {
             res_id: val.res_id,
             name: val.name,
             quantity: val.quantity,
-            is_quantity_err: val.is_quantity_err,
+            quantity_err: val.quantity_err,
             hourly_rate: "".to_owned(),
-            is_rate_err: false,
+            rate_err: None,
         }
     }
 }
@@ -71,9 +76,9 @@ impl From<NonConsumableRepr> for ConsumableRepr {
             res_id: val.res_id,
             name: val.name,
             quantity: val.quantity,
-            is_quantity_err: val.is_quantity_err,
+            quantity_err: val.quantity_err,
             cost_per_unit: "".to_owned(),
-            is_cost_err: false,
+            cost_err: None,
         }
     }
 }
@@ -111,12 +116,10 @@ impl Repr {
         }
     }
 
-    fn update_is_quantity_err(&mut self, is_err: bool) {
+    fn update_quantity_err(&mut self, err: Option<String>) {
         match self {
-            Repr::Consumable(consumable_repr) => consumable_repr.is_quantity_err = is_err,
-            Repr::NonConsumable(non_consumable_repr) => {
-                non_consumable_repr.is_quantity_err = is_err
-            }
+            Repr::Consumable(consumable_repr) => consumable_repr.quantity_err = err,
+            Repr::NonConsumable(non_consumable_repr) => non_consumable_repr.quantity_err = err,
         }
     }
 
@@ -129,12 +132,100 @@ impl Repr {
         }
     }
 
-    fn update_is_cost_err(&mut self, is_err: bool) {
+    fn update_cost_err(&mut self, err: Option<String>) {
+        match self {
+            Repr::Consumable(consumable_repr) => consumable_repr.cost_err = err,
+            Repr::NonConsumable(non_consumable_repr) => non_consumable_repr.rate_err = err,
+        }
+    }
+
+    fn csv_record(&self, extras: &ProjectExtras) -> Vec<String> {
+        let id = extras.resource_ids().id(self.res_id()).to_string();
         match self {
-            Repr::Consumable(consumable_repr) => consumable_repr.is_cost_err = is_err,
-            Repr::NonConsumable(non_consumable_repr) => non_consumable_repr.is_rate_err = is_err,
+            Repr::Consumable(consumable_repr) => vec![
+                id,
+                consumable_repr.name.clone(),
+                Selection::Consumable.to_string(),
+                consumable_repr.quantity.clone(),
+                consumable_repr.cost_per_unit.clone(),
+            ],
+            Repr::NonConsumable(non_consumable_repr) => vec![
+                id,
+                non_consumable_repr.name.clone(),
+                Selection::NonConsumable.to_string(),
+                non_consumable_repr.quantity.clone(),
+                non_consumable_repr.hourly_rate.clone(),
+            ],
         }
     }
+
+    fn rejected_fields(&self) -> Vec<&'static str> {
+        let (quantity_err, cost_err) = match self {
+            Repr::Consumable(consumable_repr) => {
+                (&consumable_repr.quantity_err, &consumable_repr.cost_err)
+            }
+            Repr::NonConsumable(non_consumable_repr) => (
+                &non_consumable_repr.quantity_err,
+                &non_consumable_repr.rate_err,
+            ),
+        };
+
+        [
+            ("Quantity", quantity_err.is_some()),
+            ("Cost", cost_err.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, is_err)| is_err.then_some(field))
+        .collect()
+    }
+}
+
+impl MaterialsState {
+    pub fn from_project(project: &Project) -> Self {
+        let repr = project
+            .resources()
+            .iter()
+            .enumerate()
+            .filter_map(|(res_id, resource)| match resource {
+                Resource::Material(material @ Material::Consumable(_)) => {
+                    Some(Repr::Consumable(ConsumableRepr {
+                        res_id,
+                        name: material.name().to_owned(),
+                        quantity: material
+                            .quantity()
+                            .map(|q| q.to_string())
+                            .unwrap_or_default(),
+                        cost_per_unit: format_cents(material.cost_per_unit()),
+                        ..Default::default()
+                    }))
+                }
+                Resource::Material(material @ Material::NonConsumable(_)) => {
+                    Some(Repr::NonConsumable(NonConsumableRepr {
+                        res_id,
+                        name: material.name().to_owned(),
+                        quantity: material
+                            .quantity()
+                            .map(|q| q.to_string())
+                            .unwrap_or_default(),
+                        hourly_rate: format_cents(material.cost_per_unit()),
+                        ..Default::default()
+                    }))
+                }
+                _ => None,
+            })
+            .collect();
+
+        MaterialsState {
+            repr,
+            ..Default::default()
+        }
+    }
+}
+
+fn format_cents(cents: Option<u16>) -> String {
+    cents
+        .map(|c| format!("{:.2}", c as f32 / 100.))
+        .unwrap_or_default()
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -164,6 +255,24 @@ pub enum MaterialsMessage {
     ResourceDeleted(usize),
 }
 
+impl MaterialsMessage {
+    /// Whether the message may change the project, rather than only what's
+    /// shown.
+    pub fn is_edit(&self) -> bool {
+        !matches!(self, MaterialsMessage::UpdateNewName(_))
+    }
+
+    /// The row of the cell the message types into, if any.
+    pub fn typed_row(&self) -> Option<usize> {
+        match self {
+            MaterialsMessage::UpdateName(i, _, _)
+            | MaterialsMessage::UpdateQuantity(i, _, _)
+            | MaterialsMessage::UpdateCost(i, _, _) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
 pub fn update(
     state: &mut MaterialsState,
     project: &mut Project,
@@ -179,41 +288,44 @@ pub fn update(
             Task::none()
         }
         MaterialsMessage::UpdateQuantity(i, res_id, q) => {
-            if let Ok(quantity) = q.parse::<u16>() {
-                match project.resource_mut(res_id).unwrap() {
-                    Resource::Material(material) => material.update_quantity(quantity),
-                    _ => panic!(),
+            match q.parse::<u16>() {
+                Ok(quantity) => {
+                    match project.resource_mut(res_id).unwrap() {
+                        Resource::Material(material) => material.update_quantity(quantity),
+                        _ => panic!(),
+                    }
+                    state.repr[i].update_quantity_err(None);
                 }
-                state.repr[i].update_is_quantity_err(false);
-            } else if q.is_empty() {
-                match project.resource_mut(res_id).unwrap() {
-                    Resource::Material(material) => material.remove_quantity(),
-                    _ => panic!(),
+                Err(_) if q.is_empty() => {
+                    match project.resource_mut(res_id).unwrap() {
+                        Resource::Material(material) => material.remove_quantity(),
+                        _ => panic!(),
+                    }
+                    state.repr[i].update_quantity_err(None);
                 }
-                state.repr[i].update_is_quantity_err(false);
-            } else {
-                state.repr[i].update_is_quantity_err(true);
+                Err(e) => state.repr[i]
+                    .update_quantity_err(Some(format!("\"{q}\" isn't a quantity: {e}"))),
             }
             state.repr[i].update_quantity(q);
             Task::none()
         }
         MaterialsMessage::UpdateCost(i, res_id, c) => {
-            if let Ok(cost) = c.parse::<f32>() {
-                match project.resource_mut(res_id).unwrap() {
-                    Resource::Material(material) => {
-                        material.update_cost_per_unit((cost * 100.) as u16)
+            match parse_amount(&c) {
+                Ok(cents) => {
+                    match project.resource_mut(res_id).unwrap() {
+                        Resource::Material(material) => material.update_cost_per_unit(cents),
+                        _ => panic!(),
                     }
-                    _ => panic!(),
+                    state.repr[i].update_cost_err(None);
                 }
-                state.repr[i].update_is_cost_err(false);
-            } else if c.is_empty() {
-                match project.resource_mut(res_id).unwrap() {
-                    Resource::Material(material) => material.remove_cost_per_unit(),
-                    _ => panic!(),
+                Err(_) if c.is_empty() => {
+                    match project.resource_mut(res_id).unwrap() {
+                        Resource::Material(material) => material.remove_cost_per_unit(),
+                        _ => panic!(),
+                    }
+                    state.repr[i].update_cost_err(None);
                 }
-                state.repr[i].update_is_cost_err(false);
-            } else {
-                state.repr[i].update_is_cost_err(true);
+                Err(e) => state.repr[i].update_cost_err(Some(e)),
             }
             state.repr[i].update_cost(c);
             Task::none()
@@ -270,7 +382,94 @@ pub fn update(
     }
 }
 
-pub fn view(state: &MaterialsState) -> Element<'_, MaterialsMessage> {
+/// Fields that can be imported from CSV, in the order of the mapped rows.
+pub const CSV_FIELDS: [&str; 4] = ["Name", "Type", "Quantity", "Cost"];
+
+pub fn csv_table(state: &MaterialsState, extras: &ProjectExtras) -> CsvTable {
+    let headers = ["Resource ID", "Name", "Type", "Quantity", "Cost"];
+
+    CsvTable {
+        headers: headers.map(ToOwned::to_owned).to_vec(),
+        rows: state.repr.iter().map(|r| r.csv_record(extras)).collect(),
+    }
+}
+
+/// Appends the rows to the project, feeding every value through [`update`]
+/// so that it's validated exactly as if it was typed in the grid.
+///
+/// Returns a description of every row with rejected values.
+pub fn import_csv(
+    state: &mut MaterialsState,
+    project: &mut Project,
+    rows: &[Vec<Option<String>>],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for (row_index, row) in rows.iter().enumerate() {
+        let field = |f: usize| row[f].clone().filter(|v| !v.is_empty());
+        let len = state.repr.len();
+
+        let _ = update(
+            state,
+            project,
+            MaterialsMessage::UpdateNewName(field(0).unwrap_or_default()),
+        );
+        let _ = update(state, project, MaterialsMessage::CreateNewMaterial);
+        if state.repr.len() == len {
+            warnings.push(format!("Materials row {}: rejected Name", row_index + 1));
+            continue;
+        }
+
+        let i = state.repr.len() - 1;
+        let res_id = state.repr[i].res_id();
+        let mut rejected = Vec::new();
+        if let Some(material_type) = field(1) {
+            match [Selection::Consumable, Selection::NonConsumable]
+                .into_iter()
+                .find(|s| s.to_string().eq_ignore_ascii_case(&material_type))
+            {
+                Some(selection) => {
+                    let _ = update(
+                        state,
+                        project,
+                        MaterialsMessage::Typeselected(i, res_id, selection),
+                    );
+                }
+                None => rejected.push("Type"),
+            }
+        }
+        if let Some(quantity) = field(2) {
+            let _ = update(
+                state,
+                project,
+                MaterialsMessage::UpdateQuantity(i, res_id, quantity),
+            );
+        }
+        if let Some(cost) = field(3) {
+            let _ = update(
+                state,
+                project,
+                MaterialsMessage::UpdateCost(i, res_id, cost),
+            );
+        }
+
+        rejected.extend(state.repr[i].rejected_fields());
+        if !rejected.is_empty() {
+            warnings.push(format!(
+                "Materials row {}: rejected {}",
+                row_index + 1,
+                rejected.join(", ")
+            ));
+        }
+    }
+
+    warnings
+}
+
+pub fn view<'a>(
+    state: &'a MaterialsState,
+    extras: &'a ProjectExtras,
+) -> Element<'a, MaterialsMessage> {
     let headers = Row::new()
         .push(data_label("Resource ID"))
         .push(data_label("Name"))
@@ -285,9 +484,9 @@ pub fn view(state: &MaterialsState) -> Element<'_, MaterialsMessage> {
         .enumerate()
         .map(|(i, r)| match r {
             Repr::Consumable(consumable) => Row::new()
-                .push(data_label(consumable.res_id))
+                .push(data_label(extras.resource_ids().id(consumable.res_id)))
                 .push(
-                    data_cell("Stimpack", &consumable.name, false)
+                    data_cell("Stimpack", &consumable.name, None)
                         .on_input(move |n| MaterialsMessage::UpdateName(i, consumable.res_id, n)),
                 )
                 .push(
@@ -297,20 +496,27 @@ pub fn view(state: &MaterialsState) -> Element<'_, MaterialsMessage> {
                     .width(constants::WIDTH),
                 )
                 .push(
-                    data_cell("1", &consumable.quantity, consumable.is_quantity_err).on_input(
-                        move |q| MaterialsMessage::UpdateQuantity(i, consumable.res_id, q),
-                    ),
+                    data_cell(
+                        "1",
+                        &consumable.quantity,
+                        consumable.quantity_err.as_deref(),
+                    )
+                    .on_input(move |q| MaterialsMessage::UpdateQuantity(i, consumable.res_id, q)),
                 )
                 .push(
-                    data_cell("20", &consumable.cost_per_unit, consumable.is_cost_err)
-                        .on_input(move |c| MaterialsMessage::UpdateCost(i, consumable.res_id, c)),
+                    data_cell(
+                        "20",
+                        &consumable.cost_per_unit,
+                        consumable.cost_err.as_deref(),
+                    )
+                    .on_input(move |c| MaterialsMessage::UpdateCost(i, consumable.res_id, c)),
                 )
                 .push(Space::new(constants::WIDTH, constants::HEIGHT))
                 .into(),
             Repr::NonConsumable(non_consumable) => Row::new()
-                .push(data_label(non_consumable.res_id))
+                .push(data_label(extras.resource_ids().id(non_consumable.res_id)))
                 .push(
-                    data_cell("Crowbar", &non_consumable.name, false).on_input(move |n| {
+                    data_cell("Crowbar", &non_consumable.name, None).on_input(move |n| {
                         MaterialsMessage::UpdateName(i, non_consumable.res_id, n)
                     }),
                 )
@@ -324,7 +530,7 @@ pub fn view(state: &MaterialsState) -> Element<'_, MaterialsMessage> {
                     data_cell(
                         "1",
                         &non_consumable.quantity,
-                        non_consumable.is_quantity_err,
+                        non_consumable.quantity_err.as_deref(),
                     )
                     .on_input(move |q| {
                         MaterialsMessage::UpdateQuantity(i, non_consumable.res_id, q)
@@ -334,7 +540,7 @@ pub fn view(state: &MaterialsState) -> Element<'_, MaterialsMessage> {
                     data_cell(
                         "20",
                         &non_consumable.hourly_rate,
-                        non_consumable.is_rate_err,
+                        non_consumable.rate_err.as_deref(),
                     )
                     .on_input(move |c| MaterialsMessage::UpdateCost(i, non_consumable.res_id, c)),
                 )
@@ -351,7 +557,7 @@ pub fn view(state: &MaterialsState) -> Element<'_, MaterialsMessage> {
         data_cell(
             "Crowbar",
             &state.new_material_name,
-            state.is_new_material_err,
+            state.new_material_err.as_deref(),
         )
         .on_input(MaterialsMessage::UpdateNewName)
         .on_submit(MaterialsMessage::CreateNewMaterial),
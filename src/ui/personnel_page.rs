@@ -8,9 +8,14 @@ use planter_core::{
     project::Project,
     resources::Resource,
 };
-use std::str::FromStr;
+use std::{collections::BTreeSet, str::FromStr};
 
-use crate::AppMessage;
+use crate::{
+    AppMessage,
+    extras::ProjectExtras,
+    io::csv::CsvTable,
+    ui::{amount::parse_amount, tasks_page::TasksMessage},
+};
 
 use super::components::{data_cell::data_cell, data_label::data_label};
 
@@ -19,22 +24,22 @@ pub struct PersonnelState {
     repr: Vec<Repr>,
     new_person_name: String,
     new_person_surname: String,
-    is_new_name_err: bool,
+    new_name_err: Option<String>,
 }
 
 #[derive(Debug, Default)]
 struct Repr {
     res_id: usize,
     first_name: String,
-    is_first_name_err: bool,
+    first_name_err: Option<String>,
     last_name: String,
-    is_last_name_err: bool,
+    last_name_err: Option<String>,
     email: String,
-    is_email_err: bool,
+    email_err: Option<String>,
     phone_number: String,
-    is_phone_err: bool,
+    phone_err: Option<String>,
     hourly_rate: String,
-    is_rate_err: bool,
+    rate_err: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -48,9 +53,70 @@ pub enum PersonnelMessage {
     UpdateNewSurname(String),
     CreateNewPersonnel,
     DeletePersonnel(usize, usize),
+    /// Adds the days off of an iCalendar file to a person, by resource index.
+    ImportDaysOff(usize),
+    ClearDaysOff(usize),
     ResourceDeleted(usize),
 }
 
+impl PersonnelMessage {
+    /// Whether the message may change the project, rather than only what's
+    /// shown.
+    pub fn is_edit(&self) -> bool {
+        !matches!(
+            self,
+            PersonnelMessage::UpdateNewName(_)
+                | PersonnelMessage::UpdateNewSurname(_)
+                | PersonnelMessage::ImportDaysOff(_)
+                | PersonnelMessage::ClearDaysOff(_)
+        )
+    }
+
+    /// The row of the cell the message types into, if any.
+    pub fn typed_row(&self) -> Option<usize> {
+        match self {
+            PersonnelMessage::UpdateName(i, _, _)
+            | PersonnelMessage::UpdateSurname(i, _, _)
+            | PersonnelMessage::UpdateEmail(i, _, _)
+            | PersonnelMessage::UpdatePhoneNumber(i, _, _)
+            | PersonnelMessage::UpdateHourlyRate(i, _, _) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
+impl PersonnelState {
+    pub fn from_project(project: &Project) -> Self {
+        let repr = project
+            .resources()
+            .iter()
+            .enumerate()
+            .filter_map(|(res_id, resource)| match resource {
+                Resource::Personnel {
+                    person,
+                    hourly_rate,
+                } => Some(Repr {
+                    res_id,
+                    first_name: person.first_name().to_owned(),
+                    last_name: person.last_name().to_owned(),
+                    email: person.email().map(|e| e.to_string()).unwrap_or_default(),
+                    phone_number: person.phone().map(|p| p.to_string()).unwrap_or_default(),
+                    hourly_rate: hourly_rate
+                        .map(|rate| format!("{:.2}", rate as f32 / 100.))
+                        .unwrap_or_default(),
+                    ..Default::default()
+                }),
+                _ => None,
+            })
+            .collect();
+
+        PersonnelState {
+            repr,
+            ..Default::default()
+        }
+    }
+}
+
 pub fn update(
     state: &mut PersonnelState,
     project: &mut Project,
@@ -60,7 +126,8 @@ pub fn update(
         PersonnelMessage::UpdateName(i, res_id, n) => {
             match project.resource_mut(res_id).unwrap() {
                 Resource::Personnel { person, .. } => {
-                    state.repr[i].is_first_name_err = person.update_first_name(&n).is_err();
+                    state.repr[i].first_name_err =
+                        person.update_first_name(&n).err().map(|e| e.to_string());
                 }
                 _ => panic!(),
             }
@@ -70,7 +137,8 @@ pub fn update(
         PersonnelMessage::UpdateSurname(i, res_id, s) => {
             match project.resource_mut(res_id).unwrap() {
                 Resource::Personnel { person, .. } => {
-                    state.repr[i].is_last_name_err = person.update_last_name(&s).is_err();
+                    state.repr[i].last_name_err =
+                        person.update_last_name(&s).err().map(|e| e.to_string());
                 }
                 _ => panic!(),
             }
@@ -83,12 +151,15 @@ pub fn update(
                 Resource::Personnel { person, .. } => {
                     if e.is_empty() {
                         person.rm_email();
-                        state.repr[i].is_email_err = false;
-                    } else if let Ok(email) = EmailAddress::from_str(&e) {
-                        person.update_email(email);
-                        state.repr[i].is_email_err = false;
+                        state.repr[i].email_err = None;
                     } else {
-                        state.repr[i].is_email_err = true;
+                        match EmailAddress::from_str(&e) {
+                            Ok(email) => {
+                                person.update_email(email);
+                                state.repr[i].email_err = None;
+                            }
+                            Err(err) => state.repr[i].email_err = Some(err.to_string()),
+                        }
                     }
                 }
                 _ => panic!(),
@@ -101,12 +172,15 @@ pub fn update(
                 Resource::Personnel { person, .. } => {
                     if p.is_empty() {
                         person.rm_phone();
-                        state.repr[i].is_phone_err = false;
-                    } else if let Ok(phone) = PhoneNumber::from_str(&p) {
-                        person.update_phone(phone);
-                        state.repr[i].is_phone_err = false;
+                        state.repr[i].phone_err = None;
                     } else {
-                        state.repr[i].is_phone_err = true;
+                        match PhoneNumber::from_str(&p) {
+                            Ok(phone) => {
+                                person.update_phone(phone);
+                                state.repr[i].phone_err = None;
+                            }
+                            Err(err) => state.repr[i].phone_err = Some(err.to_string()),
+                        }
                     }
                 }
                 _ => panic!(),
@@ -141,8 +215,12 @@ pub fn update(
                 });
                 state.new_person_name = "".to_owned();
                 state.new_person_surname = "".to_owned();
+                state.new_name_err = None;
             } else {
-                state.is_new_name_err = true;
+                state.new_name_err = Some(format!(
+                    "\"{} {}\" isn't a valid name",
+                    state.new_person_name, state.new_person_surname
+                ));
             }
             Task::none()
         }
@@ -151,21 +229,26 @@ pub fn update(
             state.repr.remove(i);
             Task::perform(async move { res_id }, AppMessage::ResourceDeleted)
         }
+        PersonnelMessage::ImportDaysOff(res_id) => Task::done(AppMessage::ImportIcs(Some(res_id))),
+        PersonnelMessage::ClearDaysOff(res_id) => Task::done(AppMessage::TasksMessage(
+            TasksMessage::UpdateDaysOff(res_id, BTreeSet::new()),
+        )),
         PersonnelMessage::UpdateHourlyRate(i, res_id, r) => {
-            if let Ok(amount) = r.parse::<f32>() {
-                match project.resource_mut(res_id).unwrap() {
-                    Resource::Personnel { hourly_rate, .. } => {
-                        *hourly_rate = Some((amount * 100.) as u16);
+            match parse_amount(&r) {
+                Ok(cents) => {
+                    match project.resource_mut(res_id).unwrap() {
+                        Resource::Personnel { hourly_rate, .. } => {
+                            *hourly_rate = Some(cents);
+                        }
+                        _ => panic!(),
                     }
-                    _ => panic!(),
+                    state.repr[i].rate_err = None;
                 }
-                state.repr[i].hourly_rate = r;
-                state.repr[i].is_rate_err = false;
-            } else if r.is_empty() {
                 // TODO: Remove rate from project
-                state.repr[i].hourly_rate = r;
-                state.repr[i].is_rate_err = false;
+                Err(_) if r.is_empty() => state.repr[i].rate_err = None,
+                Err(e) => state.repr[i].rate_err = Some(e),
             }
+            state.repr[i].hourly_rate = r;
             Task::none()
         }
         PersonnelMessage::ResourceDeleted(res_id) => {
@@ -179,14 +262,133 @@ pub fn update(
     }
 }
 
-pub fn view(state: &PersonnelState) -> Element<'_, PersonnelMessage> {
+/// Fields that can be imported from CSV, in the order of the mapped rows.
+pub const CSV_FIELDS: [&str; 5] = ["Name", "Surname", "E-Mail", "Phone", "Hourly Rate"];
+
+pub fn csv_table(state: &PersonnelState, extras: &ProjectExtras) -> CsvTable {
+    let headers = [
+        "Resource ID",
+        "Name",
+        "Surname",
+        "E-Mail",
+        "Phone",
+        "Hourly Rate",
+    ];
+    let rows = state
+        .repr
+        .iter()
+        .map(|r| {
+            vec![
+                extras.resource_ids().id(r.res_id).to_string(),
+                r.first_name.clone(),
+                r.last_name.clone(),
+                r.email.clone(),
+                r.phone_number.clone(),
+                r.hourly_rate.clone(),
+            ]
+        })
+        .collect();
+
+    CsvTable {
+        headers: headers.map(ToOwned::to_owned).to_vec(),
+        rows,
+    }
+}
+
+/// Appends the rows to the project, feeding every value through [`update`]
+/// so that it's validated exactly as if it was typed in the grid.
+///
+/// Returns a description of every row with rejected values.
+pub fn import_csv(
+    state: &mut PersonnelState,
+    project: &mut Project,
+    rows: &[Vec<Option<String>>],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for (row_index, row) in rows.iter().enumerate() {
+        let field = |f: usize| row[f].clone().filter(|v| !v.is_empty());
+        let len = state.repr.len();
+
+        let _ = update(
+            state,
+            project,
+            PersonnelMessage::UpdateNewName(field(0).unwrap_or_default()),
+        );
+        let _ = update(
+            state,
+            project,
+            PersonnelMessage::UpdateNewSurname(field(1).unwrap_or_default()),
+        );
+        let _ = update(state, project, PersonnelMessage::CreateNewPersonnel);
+        if state.repr.len() == len {
+            state.new_person_name.clear();
+            state.new_person_surname.clear();
+            state.new_name_err = None;
+            warnings.push(format!(
+                "Personnel row {}: rejected Name, Surname",
+                row_index + 1
+            ));
+            continue;
+        }
+
+        let i = state.repr.len() - 1;
+        let res_id = state.repr[i].res_id;
+        if let Some(email) = field(2) {
+            let _ = update(
+                state,
+                project,
+                PersonnelMessage::UpdateEmail(i, res_id, email),
+            );
+        }
+        if let Some(phone) = field(3) {
+            let _ = update(
+                state,
+                project,
+                PersonnelMessage::UpdatePhoneNumber(i, res_id, phone),
+            );
+        }
+        if let Some(rate) = field(4) {
+            let _ = update(
+                state,
+                project,
+                PersonnelMessage::UpdateHourlyRate(i, res_id, rate),
+            );
+        }
+
+        let r = &state.repr[i];
+        let rejected = [
+            ("E-Mail", r.email_err.is_some()),
+            ("Phone", r.phone_err.is_some()),
+            ("Hourly Rate", r.rate_err.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, is_err)| is_err.then_some(field))
+        .collect::<Vec<&str>>();
+        if !rejected.is_empty() {
+            warnings.push(format!(
+                "Personnel row {}: rejected {}",
+                row_index + 1,
+                rejected.join(", ")
+            ));
+        }
+    }
+
+    warnings
+}
+
+pub fn view<'a>(
+    state: &'a PersonnelState,
+    extras: &'a ProjectExtras,
+) -> Element<'a, PersonnelMessage> {
     let headers = Row::new()
         .push(data_label("Resource ID"))
         .push(data_label("Name"))
         .push(data_label("Surname"))
         .push(data_label("E-Mail"))
         .push(data_label("Phone"))
-        .push(data_label("Hourly Rate"));
+        .push(data_label("Hourly Rate"))
+        .push(data_label("Days Off"));
 
     let content_rows: Vec<Element<'_, _>> = state
         .repr
@@ -194,27 +396,50 @@ pub fn view(state: &PersonnelState) -> Element<'_, PersonnelMessage> {
         .enumerate()
         .map(|(i, r)| {
             Row::new()
-                .push(data_label(r.res_id))
+                .push(data_label(extras.resource_ids().id(r.res_id)))
                 .push(
-                    data_cell("Sebastiano", &r.first_name, false)
+                    data_cell("Sebastiano", &r.first_name, r.first_name_err.as_deref())
                         .on_input(move |n| PersonnelMessage::UpdateName(i, r.res_id, n)),
                 )
                 .push(
-                    data_cell("Giordano", &r.last_name, false)
+                    data_cell("Giordano", &r.last_name, r.last_name_err.as_deref())
                         .on_input(move |s| PersonnelMessage::UpdateSurname(i, r.res_id, s)),
                 )
                 .push(
-                    data_cell("sebastiano.giordano@planter.com", &r.email, r.is_email_err)
-                        .on_input(move |e| PersonnelMessage::UpdateEmail(i, r.res_id, e)),
+                    data_cell(
+                        "sebastiano.giordano@planter.com",
+                        &r.email,
+                        r.email_err.as_deref(),
+                    )
+                    .on_input(move |e| PersonnelMessage::UpdateEmail(i, r.res_id, e)),
                 )
                 .push(
-                    data_cell("+39 3284929293", &r.phone_number, r.is_phone_err)
+                    data_cell("+39 3284929293", &r.phone_number, r.phone_err.as_deref())
                         .on_input(move |p| PersonnelMessage::UpdatePhoneNumber(i, r.res_id, p)),
                 )
                 .push(
-                    data_cell("50.00", &r.hourly_rate, false)
+                    data_cell("50.00", &r.hourly_rate, r.rate_err.as_deref())
                         .on_input(move |h| PersonnelMessage::UpdateHourlyRate(i, r.res_id, h)),
                 )
+                .push(data_label(extras.days_off(r.res_id).count()))
+                .push(
+                    button("Import .ics")
+                        .on_press(PersonnelMessage::ImportDaysOff(r.res_id))
+                        .width(100)
+                        .height(50),
+                )
+                .push(
+                    button("Clear")
+                        .on_press_maybe(
+                            extras
+                                .days_off(r.res_id)
+                                .next()
+                                .is_some()
+                                .then_some(PersonnelMessage::ClearDaysOff(r.res_id)),
+                        )
+                        .width(100)
+                        .height(50),
+                )
                 .push(
                     button("Del")
                         .on_press(PersonnelMessage::DeletePersonnel(i, r.res_id))
@@ -228,18 +453,26 @@ pub fn view(state: &PersonnelState) -> Element<'_, PersonnelMessage> {
     let new_row = Row::new()
         .push(data_label(""))
         .push(
-            data_cell("Sebastiano", &state.new_person_name, state.is_new_name_err)
-                .on_input(PersonnelMessage::UpdateNewName)
-                .on_submit(PersonnelMessage::CreateNewPersonnel),
+            data_cell(
+                "Sebastiano",
+                &state.new_person_name,
+                state.new_name_err.as_deref(),
+            )
+            .on_input(PersonnelMessage::UpdateNewName)
+            .on_submit(PersonnelMessage::CreateNewPersonnel),
         )
         .push(
-            data_cell("Giordano", &state.new_person_surname, state.is_new_name_err)
-                .on_input(PersonnelMessage::UpdateNewSurname)
-                .on_submit(PersonnelMessage::CreateNewPersonnel),
+            data_cell(
+                "Giordano",
+                &state.new_person_surname,
+                state.new_name_err.as_deref(),
+            )
+            .on_input(PersonnelMessage::UpdateNewSurname)
+            .on_submit(PersonnelMessage::CreateNewPersonnel),
         )
-        .push(data_cell("", "", false))
-        .push(data_cell("", "", false))
-        .push(data_cell("", "", false));
+        .push(data_cell("", "", None))
+        .push(data_cell("", "", None))
+        .push(data_cell("", "", None));
 
     Column::new()
         .push(headers)
@@ -1,68 +1,251 @@
-use chrono::NaiveDateTime;
+use std::collections::{BTreeSet, HashMap, VecDeque, hash_map::Entry};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
 use iced::alignment::{Horizontal, Vertical};
-use iced::widget::{Column, Row, button, checkbox, container};
-use iced::{Element, Length};
+use iced::widget::{
+    Column, Row, button, checkbox, container, mouse_area, pick_list, row, text, tooltip,
+};
+use iced::{Element, Length, Padding};
 use once_cell::sync::Lazy;
 use planter_core::duration::PositiveDuration;
 use planter_core::project::Project;
 use planter_core::task::Task;
 use regex::bytes::Regex;
 
-use super::components::data_cell::data_cell;
+use crate::{
+    calendar::Calendar,
+    date_input::{self, DATE_FORMAT},
+    duration_format::DurationFormat,
+    extras::{Assignment, FULL_TIME_UNITS, Progress, ProjectExtras},
+    io::csv::CsvTable,
+    scheduling::{self, Constraint, ConstraintKind, Link, LinkKind, Schedule},
+    ui::constants,
+};
+
+use super::components::data_cell::{DataCell, critical_data_cell, data_cell};
 use super::components::data_label::data_label;
+use super::components::date_picker::date_picker;
 
 #[derive(Debug)]
 pub struct TasksState {
     repr: Vec<Repr>,
+    /// Indices of the tasks, in outline order.
+    outline: Vec<usize>,
+    /// Task selected here or in the WBS pane.
+    selected: Option<usize>,
     new_task: String,
+    /// Percent complete of the whole project.
+    project_progress: u8,
+    date_picker: Option<DatePicker>,
+}
+
+/// The date cells of a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Start,
+    Finish,
+    ActualStart,
+    ActualFinish,
+    /// The date of a constraint of some kind.
+    Constraint(ConstraintKind),
+    Deadline,
+}
+
+/// A date picker open for a date cell, showing a month.
+#[derive(Debug, Clone, Copy)]
+struct DatePicker {
+    task: usize,
+    field: DateField,
+    month: NaiveDate,
 }
 
 #[derive(Debug, Default)]
 struct Repr {
+    /// Stable ID of the task, which links refer to.
+    id: usize,
+    wbs: String,
+    depth: usize,
+    is_summary: bool,
+    is_milestone: bool,
     name: String,
     description: String,
-    completed: bool,
+    percent_complete: String,
+    percent_complete_err: Option<String>,
+    actual_start: String,
+    actual_start_err: Option<String>,
+    actual_finish: String,
+    actual_finish_err: Option<String>,
+    remaining: String,
+    remaining_err: Option<String>,
     start: String,
-    is_start_err: bool,
+    start_err: Option<String>,
     finish: String,
-    is_finish_err: bool,
+    finish_err: Option<String>,
     duration: String,
-    is_duration_err: bool,
+    duration_err: Option<String>,
     predecessors: String,
-    is_predecessors_err: bool,
+    /// Why the typed predecessors were rejected, if they were.
+    predecessors_err: Option<String>,
     successors: String,
-    is_successors_err: bool,
+    /// Why the typed successors were rejected, if they were.
+    successors_err: Option<String>,
     resources: String,
+    resources_err: Option<String>,
+    work: String,
+    work_err: Option<String>,
+    effort_driven: bool,
+    constraint_kind: ConstraintKind,
+    constraint_date: String,
+    constraint_date_err: Option<String>,
+    deadline: String,
+    deadline_err: Option<String>,
+    total_float: String,
+    free_float: String,
+    is_critical: bool,
+    /// What the task misses because of its links, if anything.
+    missed_dates: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum TasksMessage {
     UpdateName(usize, String),
     UpdateDescription(usize, String),
-    ToggleCompleted(usize),
+    UpdatePercentComplete(usize, String),
+    /// Records when a task actually started, moving it there.
+    UpdateActualStart(usize, String),
+    /// Records when a task actually finished, completing it.
+    UpdateActualFinish(usize, String),
+    /// Sets the percent complete from the duration left.
+    UpdateRemaining(usize, String),
     UpdateStart(usize, String),
     UpdateFinish(usize, String),
     UpdateDuration(usize, String),
+    /// Moves a task to a new start, keeping its duration.
+    MoveTask(usize, DateTime<Utc>),
+    /// Changes the finish of a task, keeping its start.
+    ResizeTask(usize, DateTime<Utc>),
     UpdatePredecessors(usize, String),
     UpdateSuccessors(usize, String),
+    /// Assigns resources to a task, by resource ID, with their units and
+    /// work.
     UpdateResources(usize, String),
+    /// Splits an amount of work among the resources assigned to a task.
+    UpdateWork(usize, String),
+    ToggleEffortDriven(usize),
+    /// Changes how a task is held to its constraint date. Tasks without one
+    /// take their start as the date, or their finish for constraints on the
+    /// finish.
+    SelectConstraint(usize, ConstraintKind),
+    UpdateConstraintDate(usize, String),
+    /// Sets the deadline of a task, or clears it if empty.
+    UpdateDeadline(usize, String),
+    /// Turns a task into a milestone, without duration, or back.
+    ToggleMilestone(usize),
+    /// Unassigns a resource removed from the Personnel or Materials pane.
+    ResourceDeleted(usize),
+    /// Replaces the calendar of the project. Tasks keep their duration in
+    /// working time, so their finish moves.
+    UpdateCalendar(Calendar),
+    /// Changes the time zone of the project. Dates keep their wall-clock
+    /// time.
+    UpdateTimeZone(Tz),
+    /// Replaces the days off of a person, by resource index. Their tasks keep
+    /// their duration in working time.
+    UpdateDaysOff(usize, BTreeSet<NaiveDate>),
+    /// Changes how durations, work and remaining durations are typed and
+    /// shown.
+    UpdateDurationFormat(DurationFormat),
     UpdateNewTask(String),
     CreateNewTask,
     DeleteTask(usize),
+    /// Nests a task under the task right above it at the same level.
+    Indent(usize),
+    /// Moves a task out of its summary task.
+    Outdent(usize),
+    /// Nests a task under another one, or moves it to the top level if `None`.
+    SetParent(usize, Option<usize>),
+    Select(usize),
+    /// Opens the date picker for a date cell of a task.
+    OpenDatePicker(usize, DateField),
+    /// Shows another month in the date picker.
+    ShowMonth(NaiveDate),
+    /// Sets the date the picker is open for to a day, keeping its time.
+    PickDate(NaiveDate),
+    CloseDatePicker,
+}
+
+impl TasksMessage {
+    /// The row of the cell the message types into, if any.
+    pub fn typed_row(&self) -> Option<usize> {
+        match self {
+            TasksMessage::UpdateName(i, _)
+            | TasksMessage::UpdateDescription(i, _)
+            | TasksMessage::UpdatePercentComplete(i, _)
+            | TasksMessage::UpdateActualStart(i, _)
+            | TasksMessage::UpdateActualFinish(i, _)
+            | TasksMessage::UpdateRemaining(i, _)
+            | TasksMessage::UpdateStart(i, _)
+            | TasksMessage::UpdateFinish(i, _)
+            | TasksMessage::UpdateDuration(i, _)
+            | TasksMessage::UpdatePredecessors(i, _)
+            | TasksMessage::UpdateSuccessors(i, _)
+            | TasksMessage::UpdateResources(i, _)
+            | TasksMessage::UpdateWork(i, _)
+            | TasksMessage::UpdateConstraintDate(i, _)
+            | TasksMessage::UpdateDeadline(i, _) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Whether the message may change the project, rather than only what's
+    /// shown.
+    pub fn is_edit(&self) -> bool {
+        !matches!(
+            self,
+            TasksMessage::UpdateNewTask(_)
+                | TasksMessage::Select(_)
+                | TasksMessage::OpenDatePicker(..)
+                | TasksMessage::ShowMonth(_)
+                | TasksMessage::CloseDatePicker
+        )
+    }
 }
 
 impl Default for TasksState {
     fn default() -> Self {
         TasksState {
             repr: Vec::new(),
+            outline: Vec::new(),
+            selected: None,
             new_task: "".to_owned(),
+            project_progress: 0,
+            date_picker: None,
         }
     }
 }
 
-const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+impl TasksState {
+    pub fn from_project(project: &Project, extras: &ProjectExtras) -> Self {
+        let mut state = TasksState::default();
+        update_repr(&mut state, project, extras);
+        state
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+}
+
+/// Indentation of the names of nested tasks, per level.
+const INDENT_WIDTH: f32 = 15.;
 
-pub fn update(state: &mut TasksState, project: &mut Project, message: TasksMessage) {
+pub fn update(
+    state: &mut TasksState,
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    message: TasksMessage,
+) {
     match message {
         TasksMessage::UpdateName(i, n) => {
             state.repr[i].name = n.clone();
@@ -72,296 +255,1972 @@ pub fn update(state: &mut TasksState, project: &mut Project, message: TasksMessa
             state.repr[i].description = d.clone();
             project.task_mut(i).unwrap().edit_description(d);
         }
-        TasksMessage::ToggleCompleted(i) => {
-            state.repr[i].completed = !state.repr[i].completed;
-            project.task_mut(i).unwrap().toggle_completed();
+        // The progress of summary tasks is rolled up from their children.
+        TasksMessage::UpdatePercentComplete(i, _)
+        | TasksMessage::UpdateActualStart(i, _)
+        | TasksMessage::UpdateActualFinish(i, _)
+        | TasksMessage::UpdateRemaining(i, _)
+            if state.repr[i].is_summary => {}
+        // The typed values are kept after rescheduling, which refreshes the
+        // progress of every row.
+        TasksMessage::UpdatePercentComplete(i, p) => {
+            let err = match parse_percent(&p) {
+                Some(percent_complete) => {
+                    let progress = Progress {
+                        percent_complete,
+                        ..extras.progress(i)
+                    };
+                    set_progress(state, project, extras, i, progress);
+                    None
+                }
+                None => Some(format!("\"{}\" isn't a percentage from 0 to 100", p.trim())),
+            };
+            reschedule(state, project, extras);
+            state.repr[i].percent_complete_err = err;
+            state.repr[i].percent_complete = p;
         }
-        TasksMessage::UpdateStart(i, s) => {
-            if let Ok(date) = NaiveDateTime::parse_from_str(&s, DATE_FORMAT)
-                && project
-                    .task_mut(i)
-                    .unwrap()
-                    .edit_start(date.and_utc())
-                    .is_ok()
-            {
-                update_start_finish_duration(state, project, i);
+        TasksMessage::UpdateActualStart(i, s) => {
+            let progress = extras.progress(i);
+            let err = if s.trim().is_empty() {
+                // Tasks that made some progress must have started.
+                if progress.percent_complete == 0 {
+                    set_progress(state, project, extras, i, Progress::default());
+                    None
+                } else {
+                    Some("Tasks that made progress must have started".to_owned())
+                }
             } else {
-                state.repr[i].is_start_err = true;
+                let start = parse_date(&s, extras, i, DateField::ActualStart).and_then(|date| {
+                    move_task(project, extras, i, date).map_err(|_| move_err(extras, date))?;
+                    Ok(date)
+                });
+                match start {
+                    Ok(date) => {
+                        update_start_finish_duration(state, project, extras, i);
+                        let progress = Progress {
+                            actual_start: Some(date),
+                            ..progress
+                        };
+                        set_progress(state, project, extras, i, progress);
+                        None
+                    }
+                    Err(e) => Some(e),
+                }
+            };
+            reschedule(state, project, extras);
+            state.repr[i].actual_start_err = err;
+            state.repr[i].actual_start = s;
+        }
+        TasksMessage::UpdateActualFinish(i, f) => {
+            let finish = parse_date(&f, extras, i, DateField::ActualFinish);
+            let finish = finish.and_then(|date| {
+                if state.repr[i].is_milestone {
+                    move_task(project, extras, i, date).map_err(|_| move_err(extras, date))?;
+                } else {
+                    project
+                        .task_mut(i)
+                        .unwrap()
+                        .edit_finish(date)
+                        .map_err(|_| "The actual finish can't be before the start".to_owned())?;
+                }
+                Ok(date)
+            });
+            if let Ok(finish) = finish {
+                update_start_finish_duration(state, project, extras, i);
+                fit_work(state, project, extras, i);
+                let progress = Progress {
+                    percent_complete: 100,
+                    actual_finish: Some(finish),
+                    ..extras.progress(i)
+                };
+                set_progress(state, project, extras, i, progress);
+            }
+            reschedule(state, project, extras);
+            state.repr[i].actual_finish_err = finish.err();
+            state.repr[i].actual_finish = f;
+        }
+        TasksMessage::UpdateRemaining(i, r) => {
+            let duration = scheduling::task_duration(project, extras, i).unwrap_or_default();
+            let remaining = parse_duration(&r, extras).and_then(|remaining| {
+                if remaining > duration {
+                    return Err(format!(
+                        "The task only lasts {}",
+                        extras.duration_format().format(duration)
+                    ));
+                }
+                Ok(remaining)
+            });
+            if let Ok(remaining) = remaining {
+                let percent_complete = if duration > TimeDelta::zero() {
+                    let done = (duration - remaining).num_minutes() * 100;
+                    (done as f64 / duration.num_minutes() as f64).round() as u8
+                } else {
+                    100
+                };
+                let progress = Progress {
+                    percent_complete,
+                    ..extras.progress(i)
+                };
+                set_progress(state, project, extras, i, progress);
+            }
+            reschedule(state, project, extras);
+            state.repr[i].remaining_err = remaining.err();
+            state.repr[i].remaining = r;
+        }
+        TasksMessage::UpdateStart(i, s) => {
+            let start = parse_date(&s, extras, i, DateField::Start).and_then(|date| {
+                // Tasks without a finish get one from their duration.
+                if project.task(i).unwrap().finish().is_none() {
+                    move_task(project, extras, i, date).map_err(|_| move_err(extras, date))?;
+                } else {
+                    project
+                        .task_mut(i)
+                        .unwrap()
+                        .edit_start(date)
+                        .map_err(|_| "The start can't be after the finish".to_owned())?;
+                }
+                Ok(date)
+            });
+            match start {
+                Ok(_) => {
+                    update_start_finish_duration(state, project, extras, i);
+                    fit_work(state, project, extras, i);
+                }
+                Err(e) => state.repr[i].start_err = Some(e),
             }
             state.repr[i].start = s;
+            reschedule(state, project, extras);
         }
         TasksMessage::UpdateFinish(i, s) => {
-            if let Ok(date) = NaiveDateTime::parse_from_str(&s, DATE_FORMAT)
-                && project
+            let finish = parse_date(&s, extras, i, DateField::Finish).and_then(|date| {
+                project
                     .task_mut(i)
                     .unwrap()
-                    .edit_finish(date.and_utc())
-                    .is_ok()
-            {
-                update_start_finish_duration(state, project, i);
-            } else {
-                state.repr[i].is_finish_err = true;
+                    .edit_finish(date)
+                    .map_err(|_| "The finish can't be before the start".to_owned())
+            });
+            match finish {
+                Ok(_) => {
+                    update_start_finish_duration(state, project, extras, i);
+                    fit_work(state, project, extras, i);
+                }
+                Err(e) => state.repr[i].finish_err = Some(e),
             }
             state.repr[i].finish = s;
+            reschedule(state, project, extras);
         }
         TasksMessage::UpdateDuration(i, d) => {
-            if let Ok(duration) = PositiveDuration::parse_from_str(&d) {
-                project.task_mut(i).unwrap().edit_duration(duration);
-                update_start_finish_duration(state, project, i);
-            } else {
-                state.repr[i].is_duration_err = true;
+            let duration = parse_duration(&d, extras).and_then(|duration| {
+                if duration <= TimeDelta::zero() {
+                    return Err("Durations must be longer than zero".to_owned());
+                }
+                Ok(duration)
+            });
+            match duration {
+                Ok(duration) => {
+                    if set_duration(project, extras, i, duration).is_ok() {
+                        update_start_finish_duration(state, project, extras, i);
+                        fit_work(state, project, extras, i);
+                    } else {
+                        state.repr[i].duration_err = Some(format!(
+                            "The task can't last {}",
+                            extras.duration_format().format(duration)
+                        ));
+                    }
+                }
+                Err(e) => state.repr[i].duration_err = Some(e),
             }
             state.repr[i].duration = d;
+            reschedule(state, project, extras);
         }
-        TasksMessage::UpdateResources(_i, _s) => {}
-        TasksMessage::UpdatePredecessors(i, p) => {
-            let predecessors = project.predecessors_indices(i).collect::<Vec<usize>>();
-            let is_failure = if let Some(indices) = parse_indices(&p) {
-                project.update_predecessors(i, &indices).is_err()
+        TasksMessage::MoveTask(i, start) if state.repr[i].is_summary => {
+            // Summary tasks follow their children, so move the children instead.
+            let Some(delta) = project.task(i).unwrap().start().map(|s| start - s) else {
+                return;
+            };
+            for descendant in 0..state.repr.len() {
+                if state.repr[descendant].is_summary || !extras.is_ancestor(i, descendant) {
+                    continue;
+                }
+                if let Some(start) = project.task(descendant).unwrap().start() {
+                    let moved = move_task(project, extras, descendant, start + delta);
+                    update_start_finish_duration(state, project, extras, descendant);
+                    if moved.is_err() {
+                        state.repr[descendant].start_err = Some(move_err(extras, start + delta));
+                    }
+                }
+            }
+            reschedule(state, project, extras);
+        }
+        TasksMessage::MoveTask(i, start) => {
+            if move_task(project, extras, i, start).is_ok() {
+                update_start_finish_duration(state, project, extras, i);
+                reschedule(state, project, extras);
+            } else {
+                state.repr[i].start_err = Some(move_err(extras, start));
+            }
+        }
+        TasksMessage::ResizeTask(i, _) if state.repr[i].is_summary => {}
+        TasksMessage::ResizeTask(i, finish) => {
+            if project.task_mut(i).unwrap().edit_finish(finish).is_ok() {
+                update_start_finish_duration(state, project, extras, i);
+                fit_work(state, project, extras, i);
+                reschedule(state, project, extras);
+            } else {
+                state.repr[i].finish_err = Some("The finish can't be before the start".to_owned());
+            }
+        }
+        TasksMessage::UpdateResources(i, r) => {
+            match parse_assignments(
+                &r,
+                |id| resource_index(project, extras, id),
+                extras.duration_format(),
+            ) {
+                Ok(assignments) => {
+                    // Effort-driven tasks keep their work, unless it's given explicitly.
+                    let work = extras.work(i);
+                    let keeps_work = extras.is_effort_driven(i)
+                        && work > TimeDelta::zero()
+                        && assignments.iter().all(|(_, _, work)| work.is_none());
+                    let duration =
+                        scheduling::task_duration(project, extras, i).unwrap_or_default();
+                    let assignments = assignments
+                        .into_iter()
+                        .map(|(res_id, units, work)| {
+                            let assignment = match work {
+                                Some(work) => Assignment { units, work },
+                                None => Assignment::for_duration(units, duration),
+                            };
+                            (res_id, assignment)
+                        })
+                        .collect::<Vec<_>>();
+                    extras.set_assignments(i, &assignments);
+                    // The days off of the new people may change the working time.
+                    let kept = keep_duration(project, extras, i, Some(duration));
+                    if keeps_work {
+                        extras.set_work(i, work);
+                    }
+                    apply_effort(state, project, extras, i);
+                    if let Err(e) = kept {
+                        state.repr[i].duration_err = Some(e);
+                    }
+                }
+                Err(e) => state.repr[i].resources_err = Some(e),
+            }
+            state.repr[i].resources = r;
+            reschedule(state, project, extras);
+        }
+        TasksMessage::UpdateWork(i, w) => {
+            let work = parse_duration(&w, extras).and_then(|work| {
+                if extras.assignments(i).next().is_none() {
+                    return Err("Assign resources to the task before its work".to_owned());
+                }
+                Ok(work)
+            });
+            match work {
+                Ok(work) => {
+                    extras.set_work(i, work);
+                    apply_effort(state, project, extras, i);
+                }
+                Err(e) => state.repr[i].work_err = Some(e),
+            }
+            state.repr[i].work = w;
+            reschedule(state, project, extras);
+        }
+        TasksMessage::ToggleEffortDriven(i) => {
+            state.repr[i].effort_driven = !state.repr[i].effort_driven;
+            extras.set_effort_driven(i, state.repr[i].effort_driven);
+        }
+        // Summary tasks follow their children, so they're held to no dates.
+        TasksMessage::SelectConstraint(i, _)
+        | TasksMessage::UpdateConstraintDate(i, _)
+        | TasksMessage::UpdateDeadline(i, _)
+            if state.repr[i].is_summary => {}
+        TasksMessage::SelectConstraint(i, kind) => {
+            let task = project.task(i).unwrap();
+            let date = extras
+                .constraint(i)
+                .map(|c| c.date)
+                .or(if kind.is_finish() {
+                    task.finish().or(task.start())
+                } else {
+                    task.start()
+                });
+            state.repr[i].constraint_kind = kind;
+            match date {
+                _ if kind == ConstraintKind::AsSoonAsPossible => {
+                    extras.set_constraint(i, None);
+                    update_constraint_repr(state, extras, i);
+                }
+                Some(date) => {
+                    extras.set_constraint(i, Some(Constraint { kind, date }));
+                    update_constraint_repr(state, extras, i);
+                }
+                // The date has to be typed in.
+                None => {
+                    extras.set_constraint(i, None);
+                    state.repr[i].constraint_date_err =
+                        Some(format!("Type the date of the {kind} constraint"));
+                }
+            }
+            reschedule(state, project, extras);
+        }
+        TasksMessage::UpdateConstraintDate(i, d) => {
+            let kind = state.repr[i].constraint_kind;
+            let date = if kind == ConstraintKind::AsSoonAsPossible {
+                Err(format!("{kind} constraints have no date"))
+            } else {
+                parse_date(&d, extras, i, DateField::Constraint(kind))
+            };
+            if let Ok(date) = date {
+                extras.set_constraint(i, Some(Constraint { kind, date }));
+            }
+            reschedule(state, project, extras);
+            state.repr[i].constraint_date_err = date.err();
+            state.repr[i].constraint_date = d;
+        }
+        TasksMessage::UpdateDeadline(i, d) => {
+            let deadline = if d.trim().is_empty() {
+                Ok(None)
             } else {
-                true
+                parse_date(&d, extras, i, DateField::Deadline).map(Some)
             };
+            if let Ok(deadline) = deadline {
+                extras.set_deadline(i, deadline);
+            }
+            reschedule(state, project, extras);
+            state.repr[i].deadline_err = deadline.err();
+            state.repr[i].deadline = d;
+        }
+        TasksMessage::ToggleMilestone(i) if state.repr[i].is_summary => {}
+        TasksMessage::ToggleMilestone(i) => {
+            let is_milestone = !state.repr[i].is_milestone;
+            let mut duration_err = None;
+            if is_milestone {
+                extras.set_milestone_duration(i, scheduling::task_duration(project, extras, i));
+                extras.set_unscheduled_duration(i, None);
+                // Milestones have a start, but neither finish nor duration.
+                // A finish or a duration can't be taken off a task, so the
+                // milestone takes everything else from it.
+                let task = project.task_mut(i).unwrap();
+                let mut milestone = Task::new(task.name().to_owned());
+                milestone.edit_description(task.description().to_owned());
+                if task.completed() {
+                    milestone.toggle_completed();
+                }
+                if let Some(start) = task.start() {
+                    let _ = milestone.edit_start(start);
+                }
+                *task = milestone;
+            } else {
+                if let Some(duration) = extras.milestone_duration(i) {
+                    if set_duration(project, extras, i, duration).is_err() {
+                        duration_err = Some(format!(
+                            "The task can't last {} again",
+                            extras.duration_format().format(duration)
+                        ));
+                    }
+                }
+                extras.set_milestone_duration(i, None);
+            }
+            extras.set_milestone(i, is_milestone);
+            state.repr[i].is_milestone = is_milestone;
+            state.repr[i].finish = "".to_owned();
+            state.repr[i].duration = "".to_owned();
+            update_start_finish_duration(state, project, extras, i);
+            reschedule(state, project, extras);
+            state.repr[i].duration_err = duration_err;
+        }
+        TasksMessage::ResourceDeleted(res_id) => {
+            keep_durations(state, project, extras, |extras| {
+                extras.remove_resource(res_id)
+            });
+            for i in 0..state.repr.len() {
+                if state.repr[i].resources_err.is_none() {
+                    update_work_repr(state, extras, i);
+                }
+            }
+        }
+        TasksMessage::UpdateCalendar(calendar) => {
+            keep_durations(state, project, extras, |extras| {
+                extras.set_calendar(calendar)
+            });
+        }
+        TasksMessage::UpdateTimeZone(time_zone) => {
+            // Dates keep their wall-clock time, as if they had been typed in
+            // the new time zone.
+            let old_calendar = extras.calendar().clone();
+            let mut calendar = old_calendar.clone();
+            calendar.set_time_zone(time_zone);
+            let shift = |date: DateTime<Utc>| calendar.to_utc(old_calendar.to_local(date));
+            // Tasks whose shifted dates are rejected stay where they were.
+            let mut unmoved = Vec::new();
+            for i in 0..state.repr.len() {
+                let task = project.task_mut(i).unwrap();
+                let moved = match (task.start(), task.finish()) {
+                    (Some(start), Some(finish)) => set_dates(task, shift(start), shift(finish)),
+                    (Some(start), None) => {
+                        task.edit_start(shift(start)).map_or(Err(()), |_| Ok(()))
+                    }
+                    _ => Ok(()),
+                };
+                if moved.is_err() {
+                    unmoved.push((i, shift(task.start().unwrap())));
+                }
+                let progress = extras.progress(i);
+                extras.set_progress(
+                    i,
+                    Progress {
+                        actual_start: progress.actual_start.map(shift),
+                        actual_finish: progress.actual_finish.map(shift),
+                        ..progress
+                    },
+                );
+                let constraint = extras.constraint(i).map(|constraint| Constraint {
+                    date: shift(constraint.date),
+                    ..constraint
+                });
+                extras.set_constraint(i, constraint);
+                extras.set_deadline(i, extras.deadline(i).map(shift));
+            }
+            extras.set_calendar(calendar);
+            update_repr(state, project, extras);
+            reschedule(state, project, extras);
+            for (i, start) in unmoved {
+                state.repr[i].start_err = Some(move_err(extras, start));
+            }
+        }
+        TasksMessage::UpdateDaysOff(res_id, days_off) => {
+            keep_durations(state, project, extras, |extras| {
+                extras.set_days_off(res_id, days_off)
+            });
+        }
+        TasksMessage::UpdateDurationFormat(duration_format) => {
+            extras.set_duration_format(duration_format);
+            update_repr(state, project, extras);
+        }
+        TasksMessage::UpdatePredecessors(i, p) => {
+            let predecessors = project.predecessors_indices(i).collect::<Vec<usize>>();
+            let links = resolve_links(project, extras, &p, |predecessor| (predecessor, i))
+                .and_then(|links| {
+                    let indices = links.iter().map(|(p, _)| *p).collect::<Vec<usize>>();
+                    project
+                        .update_predecessors(i, &indices)
+                        .map_err(|_| "These links can't be made".to_owned())?;
+                    Ok(links)
+                });
 
-            if is_failure {
-                project
-                    .update_predecessors(i, &[])
-                    .expect("It should have been possible to remove predecessors. This is a bug.");
+            // Rejected links leave the previous ones in place.
+            match links {
+                Ok(links) => {
+                    extras.retain_links(i, &links.iter().map(|(p, _)| *p).collect::<Vec<usize>>());
+                    for (predecessor, link) in links {
+                        extras.set_link(predecessor, i, link);
+                    }
+                    state.repr[i].predecessors_err = None;
+                }
+                Err(e) => {
+                    project.update_predecessors(i, &predecessors).expect(
+                        "It should have been possible to restore predecessors. This is a bug.",
+                    );
+                    state.repr[i].predecessors_err = Some(e);
+                }
             }
 
             update_predecessors_repr(
                 state,
                 project,
+                extras,
                 &project
                     .predecessors_indices(i)
                     .filter(|index| !predecessors.contains(index))
                     .collect::<Vec<usize>>(),
             );
-            update_predecessors_repr(state, project, &predecessors);
+            update_predecessors_repr(state, project, extras, &predecessors);
 
-            state.repr[i].is_predecessors_err = is_failure;
             state.repr[i].predecessors = p;
+            reschedule(state, project, extras);
         }
         TasksMessage::UpdateSuccessors(i, p) => {
             let successors = project.successors_indices(i).collect::<Vec<usize>>();
-            let is_failure = if let Some(indices) = parse_indices(&p) {
-                project.update_successors(i, &indices).is_err()
-            } else {
-                true
-            };
-            if is_failure {
-                project
-                    .update_successors(i, &[])
-                    .expect("It should have been possible to remove predecessors. This is a bug.");
+            let links =
+                resolve_links(project, extras, &p, |successor| (i, successor)).and_then(|links| {
+                    let indices = links.iter().map(|(s, _)| *s).collect::<Vec<usize>>();
+                    project
+                        .update_successors(i, &indices)
+                        .map_err(|_| "These links can't be made".to_owned())?;
+                    Ok(links)
+                });
+
+            // Rejected links leave the previous ones in place.
+            match links {
+                Ok(links) => {
+                    for &successor in &successors {
+                        extras.set_link(i, successor, Link::default());
+                    }
+                    for (successor, link) in links {
+                        extras.set_link(i, successor, link);
+                    }
+                    state.repr[i].successors_err = None;
+                }
+                Err(e) => {
+                    project.update_successors(i, &successors).expect(
+                        "It should have been possible to restore successors. This is a bug.",
+                    );
+                    state.repr[i].successors_err = Some(e);
+                }
             }
 
             // Update old and new successors.
             update_successors_repr(
                 state,
                 project,
+                extras,
                 &project
                     .successors_indices(i)
                     .filter(|index| !successors.contains(index))
                     .collect::<Vec<usize>>(),
             );
-            update_successors_repr(state, project, &successors);
+            update_successors_repr(state, project, extras, &successors);
 
-            state.repr[i].is_successors_err = is_failure;
             state.repr[i].successors = p;
+            reschedule(state, project, extras);
         }
         TasksMessage::CreateNewTask => {
             let task = Task::new(state.new_task.clone());
             project.add_task(task);
             state.repr.push(Repr {
+                id: extras.task_ids().id(state.repr.len()),
                 name: state.new_task.clone(),
                 ..Default::default()
             });
             state.new_task = "".to_owned();
+            update_outline(state, project, extras);
         }
         TasksMessage::UpdateNewTask(n) => state.new_task = n,
         TasksMessage::DeleteTask(i) => {
             project
                 .rm_task(i)
                 .expect("Should have been possible to remove a task. This is a bug.");
-            update_repr(state, project);
+            extras.remove_task(i);
+            state.date_picker = None;
+            state.selected = match state.selected {
+                Some(selected) if selected == i => None,
+                Some(selected) if selected > i => Some(selected - 1),
+                selected => selected,
+            };
+            update_repr(state, project, extras);
+            reschedule(state, project, extras);
+        }
+        TasksMessage::Indent(i) => {
+            extras.indent(i, state.repr.len());
+            update_outline(state, project, extras);
+            reschedule(state, project, extras);
+        }
+        TasksMessage::Outdent(i) => {
+            extras.outdent(i);
+            update_outline(state, project, extras);
+            reschedule(state, project, extras);
+        }
+        TasksMessage::SetParent(i, parent) => {
+            // Nesting a task under one of its own descendants is ignored.
+            if extras.set_parent(i, parent).is_ok() {
+                update_outline(state, project, extras);
+                reschedule(state, project, extras);
+            }
         }
+        TasksMessage::Select(i) => {
+            state.selected = (state.selected != Some(i)).then_some(i);
+        }
+        TasksMessage::OpenDatePicker(i, field) => {
+            let date = field_date(project, extras, i, field).unwrap_or_else(Utc::now);
+            state.date_picker = Some(DatePicker {
+                task: i,
+                field,
+                month: extras.calendar().to_local(date).date(),
+            });
+        }
+        TasksMessage::ShowMonth(month) => {
+            if let Some(date_picker) = &mut state.date_picker {
+                date_picker.month = month;
+            }
+        }
+        TasksMessage::PickDate(day) => {
+            let Some(DatePicker { task, field, .. }) = state.date_picker.take() else {
+                return;
+            };
+            // Days without a time start or finish with the working day.
+            let date = match field_date(project, extras, task, field) {
+                Some(date) => {
+                    let time = extras.calendar().to_local(date).time();
+                    day.and_time(time).format(DATE_FORMAT).to_string()
+                }
+                None => day.to_string(),
+            };
+            let message = match field {
+                DateField::Start => TasksMessage::UpdateStart(task, date),
+                DateField::Finish => TasksMessage::UpdateFinish(task, date),
+                DateField::ActualStart => TasksMessage::UpdateActualStart(task, date),
+                DateField::ActualFinish => TasksMessage::UpdateActualFinish(task, date),
+                DateField::Constraint(_) => TasksMessage::UpdateConstraintDate(task, date),
+                DateField::Deadline => TasksMessage::UpdateDeadline(task, date),
+            };
+            update(state, project, extras, message);
+        }
+        TasksMessage::CloseDatePicker => state.date_picker = None,
     }
 }
 
-fn update_start_finish_duration(state: &mut TasksState, project: &Project, task_index: usize) {
+/// Parses a date typed in a date cell of a task. Days without a time start
+/// or finish with the working day of the task, depending on the cell.
+fn parse_date(
+    s: &str,
+    extras: &ProjectExtras,
+    task_index: usize,
+    field: DateField,
+) -> Result<DateTime<Utc>, String> {
+    let calendar = extras.task_calendar(task_index);
+    let at_default_time = |day| match field {
+        DateField::Finish | DateField::ActualFinish | DateField::Deadline => {
+            calendar.day_finish(day)
+        }
+        DateField::Constraint(kind) if kind.is_finish() => calendar.day_finish(day),
+        _ => calendar.day_start(day),
+    };
+
+    date_input::parse(s, Utc::now(), &calendar, at_default_time).ok_or_else(|| {
+        format!(
+            "\"{}\" isn't a date, such as 2025-05-27 09:00, 27 May 2025 or +3d",
+            s.trim()
+        )
+    })
+}
+
+/// Parses a duration typed in a cell, in the duration format of the project.
+fn parse_duration(s: &str, extras: &ProjectExtras) -> Result<TimeDelta, String> {
+    extras
+        .duration_format()
+        .parse(s)
+        .ok_or_else(|| format!("\"{}\" isn't a duration, such as 1w 2d or 16h", s.trim()))
+}
+
+/// Formats a date in the time zone of the project.
+fn format_date(extras: &ProjectExtras, date: DateTime<Utc>) -> String {
+    extras
+        .calendar()
+        .to_local(date)
+        .format(DATE_FORMAT)
+        .to_string()
+}
+
+/// The date in a date cell of a task.
+fn field_date(
+    project: &Project,
+    extras: &ProjectExtras,
+    task_index: usize,
+    field: DateField,
+) -> Option<DateTime<Utc>> {
+    let task = project.task(task_index)?;
+    match field {
+        DateField::Start => task.start(),
+        DateField::Finish => task.finish(),
+        DateField::ActualStart => extras.progress(task_index).actual_start,
+        DateField::ActualFinish => extras.progress(task_index).actual_finish,
+        DateField::Constraint(_) => extras.constraint(task_index).map(|c| c.date),
+        DateField::Deadline => extras.deadline(task_index),
+    }
+}
+
+/// Applies a change to the working time of tasks, moving their finish so that
+/// they keep their duration.
+fn keep_durations(
+    state: &mut TasksState,
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    change: impl FnOnce(&mut ProjectExtras),
+) {
+    let durations = (0..state.repr.len())
+        .map(|i| scheduling::task_duration(project, extras, i))
+        .collect::<Vec<_>>();
+    change(extras);
+    for (i, duration) in durations.into_iter().enumerate() {
+        let kept = keep_duration(project, extras, i, duration);
+        update_start_finish_duration(state, project, extras, i);
+        if let Err(e) = kept {
+            state.repr[i].duration_err = Some(e);
+        }
+    }
+    reschedule(state, project, extras);
+}
+
+/// Moves the finish of a task so that it lasts `duration` in its current
+/// working time, and explains why if it can't.
+fn keep_duration(
+    project: &mut Project,
+    extras: &ProjectExtras,
+    task_index: usize,
+    duration: Option<TimeDelta>,
+) -> Result<(), String> {
+    let task = project.task_mut(task_index).unwrap();
+    if let (Some(start), Some(_), Some(duration)) = (task.start(), task.finish(), duration) {
+        set_dates(
+            task,
+            start,
+            extras.task_calendar(task_index).add(start, duration),
+        )
+        .map_err(|_| {
+            format!(
+                "The task can't last {} in its new working time",
+                extras.duration_format().format(duration)
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Moves a task to a new start, keeping its duration in working time. Fails,
+/// leaving the task as it was, if the new dates are rejected.
+fn move_task(
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    task_index: usize,
+    start: DateTime<Utc>,
+) -> Result<(), ()> {
+    // Milestones have no duration to keep.
+    let duration = scheduling::task_duration(project, extras, task_index)
+        .filter(|_| !extras.is_milestone(task_index));
+    let task = project.task_mut(task_index).unwrap();
+    match (task.start(), task.finish()) {
+        (Some(old_start), Some(old_finish)) => {
+            let calendar = extras.task_calendar(task_index);
+            let duration = calendar.working_time(old_start, old_finish);
+            set_dates(task, start, calendar.add(start, duration))
+        }
+        (old_start, _) => {
+            task.edit_start(start).map_err(|_| ())?;
+            if let Some(duration) = duration
+                && set_duration(project, extras, task_index, duration).is_err()
+            {
+                if let Some(old_start) = old_start {
+                    let _ = project.task_mut(task_index).unwrap().edit_start(old_start);
+                }
+                return Err(());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Gives a task a duration in working time, which moves its finish if it has
+/// a start. planter-core only keeps whole hours for tasks without a start, so
+/// the extras keep their exact duration. Fails, leaving the task as it was, if
+/// the duration is rejected.
+fn set_duration(
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    task_index: usize,
+    duration: TimeDelta,
+) -> Result<(), ()> {
+    let task = project.task_mut(task_index).unwrap();
+    match task.start() {
+        Some(start) => {
+            let finish = extras.task_calendar(task_index).add(start, duration);
+            task.edit_finish(finish).map_err(|_| ())?;
+            extras.set_unscheduled_duration(task_index, None);
+        }
+        None => {
+            let hours = (duration.num_minutes() + 59) / 60;
+            let hours = PositiveDuration::parse_from_str(&format!("{hours} h")).map_err(|_| ())?;
+            task.edit_duration(hours);
+            extras.set_unscheduled_duration(task_index, Some(duration));
+        }
+    }
+    Ok(())
+}
+
+/// Gives a task the duration its resources need to complete their work, if
+/// they have any.
+fn apply_effort(
+    state: &mut TasksState,
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    task_index: usize,
+) {
+    if !extras.is_milestone(task_index)
+        && let Some(duration) = extras.effort_duration(task_index)
+        && duration > TimeDelta::zero()
+    {
+        let is_set = set_duration(project, extras, task_index, duration).is_ok();
+        update_start_finish_duration(state, project, extras, task_index);
+        if !is_set {
+            state.repr[task_index].work_err = Some(format!(
+                "The task can't last the {} this work takes",
+                extras.duration_format().format(duration)
+            ));
+        }
+    }
+    update_work_repr(state, extras, task_index);
+}
+
+/// Changes the work of the resources assigned to a task to match its new
+/// duration.
+fn fit_work(
+    state: &mut TasksState,
+    project: &Project,
+    extras: &mut ProjectExtras,
+    task_index: usize,
+) {
+    if let Some(duration) = scheduling::task_duration(project, extras, task_index) {
+        extras.fit_work_to_duration(task_index, duration);
+        update_work_repr(state, extras, task_index);
+    }
+}
+
+/// Gives a task new dates. Fails, leaving the task as it was, if they're
+/// rejected.
+fn set_dates(task: &mut Task, start: DateTime<Utc>, finish: DateTime<Utc>) -> Result<(), ()> {
+    let old_dates = (task.start(), task.finish());
+    if edit_dates(task, start, finish).is_err() {
+        match old_dates {
+            (Some(old_start), Some(old_finish)) => {
+                let _ = edit_dates(task, old_start, old_finish);
+            }
+            (Some(old_start), None) => {
+                let _ = task.edit_start(old_start);
+            }
+            _ => {}
+        }
+        return Err(());
+    }
+    Ok(())
+}
+
+fn edit_dates(task: &mut Task, start: DateTime<Utc>, finish: DateTime<Utc>) -> Result<(), ()> {
+    // Edit first the bound that moves away from the other one, so that start
+    // never goes past finish.
+    let edited = if task.finish().is_some_and(|old_finish| start > old_finish) {
+        task.edit_finish(finish)
+            .and_then(|_| task.edit_start(start))
+    } else {
+        task.edit_start(start)
+            .and_then(|_| task.edit_finish(finish))
+    };
+    edited.map_err(|_| ())?;
+    Ok(())
+}
+
+/// Explains why a task was left where it was.
+fn move_err(extras: &ProjectExtras, start: DateTime<Utc>) -> String {
+    format!("The task can't be moved to {}", format_date(extras, start))
+}
+
+/// Moves every task to the earliest start allowed by its predecessors, rolls
+/// summary tasks up from their children, and refreshes the rows that changed.
+fn reschedule(state: &mut TasksState, project: &mut Project, extras: &mut ProjectExtras) {
+    for (i, schedule) in scheduling::schedule(project, extras)
+        .into_iter()
+        .enumerate()
+    {
+        let Some(schedule) = schedule else {
+            continue;
+        };
+        let task = project.task_mut(i).unwrap();
+        // Finishes can move on their own, when the calendar changes.
+        if state.repr[i].is_summary || task.finish().is_some() {
+            if (task.start(), task.finish())
+                != (Some(schedule.early_start), Some(schedule.early_finish))
+            {
+                let moved = set_dates(task, schedule.early_start, schedule.early_finish);
+                update_start_finish_duration(state, project, extras, i);
+                if moved.is_err() {
+                    state.repr[i].start_err = Some(move_err(extras, schedule.early_start));
+                }
+            }
+        } else if task.start() != Some(schedule.early_start) {
+            let moved = move_task(project, extras, i, schedule.early_start);
+            update_start_finish_duration(state, project, extras, i);
+            if moved.is_err() {
+                state.repr[i].start_err = Some(move_err(extras, schedule.early_start));
+            }
+        }
+    }
+
+    // Children come after their parents in the outline, so going backwards
+    // rolls up nested summary tasks before their own parents.
+    for i in state.outline.clone().into_iter().rev() {
+        if !state.repr[i].is_summary {
+            continue;
+        }
+        let progress = roll_up_progress(project, extras, Some(i));
+        set_progress(state, project, extras, i, progress);
+    }
+    state.project_progress = roll_up_progress(project, extras, None).percent_complete;
+
+    // The remaining duration follows the duration.
+    for i in 0..state.repr.len() {
+        if state.repr[i].remaining_err.is_none() {
+            state.repr[i].remaining = format_remaining(project, extras, i);
+        }
+    }
+
+    update_float(state, project, extras);
+}
+
+/// Progress of the children of a summary task, or of the top-level tasks if
+/// `None`.
+fn roll_up_progress(project: &Project, extras: &ProjectExtras, parent: Option<usize>) -> Progress {
+    Progress::roll_up(
+        extras
+            .children(parent, project.tasks().count())
+            .into_iter()
+            .map(|c| {
+                (
+                    extras.progress(c),
+                    scheduling::task_duration(project, extras, c).unwrap_or_default(),
+                )
+            }),
+    )
+}
+
+/// Records the progress of a task, filling in the actual dates it implies,
+/// and whether it's completed.
+fn set_progress(
+    state: &mut TasksState,
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    task_index: usize,
+    mut progress: Progress,
+) {
+    let task = project.task_mut(task_index).unwrap();
+    if progress.percent_complete > 0 && progress.actual_start.is_none() {
+        progress.actual_start = task.start();
+    }
+    if progress.percent_complete < 100 {
+        progress.actual_finish = None;
+    } else if progress.actual_finish.is_none() {
+        // Milestones finish when they start.
+        progress.actual_finish = task.finish().or(task.start());
+    }
+    if task.completed() != (progress.percent_complete == 100) {
+        task.toggle_completed();
+    }
+    extras.set_progress(task_index, progress);
+    update_progress_repr(state, project, extras, task_index);
+}
+
+/// Refreshes the outline numbers and nesting of every row.
+fn update_outline(state: &mut TasksState, project: &Project, extras: &ProjectExtras) {
+    let task_count = project.tasks().count();
+    state.outline.clear();
+    for entry in extras.outline(task_count) {
+        let r = &mut state.repr[entry.task];
+        r.wbs = entry.number;
+        r.depth = entry.depth;
+        r.is_summary = extras.is_summary(entry.task, task_count);
+        state.outline.push(entry.task);
+    }
+}
+
+/// Refreshes the float of every row, whether it's on the critical path, and
+/// the dates it misses.
+fn update_float(state: &mut TasksState, project: &Project, extras: &ProjectExtras) {
+    for (i, (r, schedule)) in state
+        .repr
+        .iter_mut()
+        .zip(scheduling::schedule(project, extras))
+        .enumerate()
+    {
+        match schedule {
+            Some(schedule) => {
+                r.total_float = format_float(extras, schedule.total_float);
+                r.free_float = format_float(extras, schedule.free_float);
+                r.is_critical = schedule.is_critical();
+                r.missed_dates = describe_missed_dates(extras, i, &schedule);
+            }
+            None => {
+                r.total_float = "".to_owned();
+                r.free_float = "".to_owned();
+                r.is_critical = false;
+                r.missed_dates = None;
+            }
+        }
+    }
+}
+
+/// Formats a float like durations. Floats are negative for tasks pushed past
+/// their constraint.
+fn format_float(extras: &ProjectExtras, float: TimeDelta) -> String {
+    let duration_format = extras.duration_format();
+    if float < TimeDelta::zero() {
+        format!("-{}", duration_format.format(-float))
+    } else {
+        duration_format.format(float)
+    }
+}
+
+/// Tells which of its constraint and deadline a task misses, and which link
+/// pushes it past them, if any.
+fn describe_missed_dates(
+    extras: &ProjectExtras,
+    task_index: usize,
+    schedule: &Schedule,
+) -> Option<String> {
+    let constraint = extras
+        .constraint(task_index)
+        .filter(|_| schedule.misses_constraint)
+        .map(|c| {
+            format!(
+                "its {} constraint on {}",
+                c.kind,
+                format_date(extras, c.date)
+            )
+        });
+    let deadline = extras
+        .deadline(task_index)
+        .filter(|_| schedule.misses_deadline)
+        .map(|d| format!("its deadline on {}", format_date(extras, d)));
+    let missed = constraint
+        .into_iter()
+        .chain(deadline)
+        .collect::<Vec<String>>();
+    if missed.is_empty() {
+        return None;
+    }
+
+    let cause = match schedule.driving_link {
+        Some((p, link)) => {
+            let p = extras.task_ids().id(p);
+            let lag = if link.lag > TimeDelta::zero() {
+                format!(
+                    " with a lag of {}",
+                    extras.duration_format().format(link.lag)
+                )
+            } else if link.lag < TimeDelta::zero() {
+                format!(
+                    " with a lead of {}",
+                    extras.duration_format().format(-link.lag)
+                )
+            } else {
+                "".to_owned()
+            };
+            format!("the {} link from task {p}{lag}", link.kind)
+        }
+        None => "its own dates".to_owned(),
+    };
+    Some(format!(
+        "Misses {}, because of {cause}.",
+        missed.join(" and ")
+    ))
+}
+
+fn update_start_finish_duration(
+    state: &mut TasksState,
+    project: &Project,
+    extras: &ProjectExtras,
+    task_index: usize,
+) {
     let task = project.task(task_index).unwrap();
 
     if let Some(start) = task.start() {
-        state.repr[task_index].start = start.naive_local().format(DATE_FORMAT).to_string();
-        state.repr[task_index].is_start_err = false;
+        state.repr[task_index].start = format_date(extras, start);
+        state.repr[task_index].start_err = None;
     }
     if let Some(finish) = task.finish() {
-        state.repr[task_index].finish = finish.naive_local().format(DATE_FORMAT).to_string();
-        state.repr[task_index].is_finish_err = false;
+        state.repr[task_index].finish = format_date(extras, finish);
+        state.repr[task_index].finish_err = None;
+    }
+    if let Some(duration) = scheduling::task_duration(project, extras, task_index) {
+        state.repr[task_index].duration = extras.duration_format().format(duration);
+        state.repr[task_index].duration_err = None;
     }
-    if let Some(duration) = task.duration() {
-        state.repr[task_index].duration = format!("{} h", duration.num_hours());
-        state.repr[task_index].is_duration_err = false;
+    if state.repr[task_index].is_milestone {
+        state.repr[task_index].finish = state.repr[task_index].start.clone();
     }
 }
 
-fn update_repr(state: &mut TasksState, project: &mut Project) {
+fn update_progress_repr(
+    state: &mut TasksState,
+    project: &Project,
+    extras: &ProjectExtras,
+    task_index: usize,
+) {
+    let progress = extras.progress(task_index);
+    let format_actual =
+        |date: Option<DateTime<Utc>>| date.map_or("".to_owned(), |date| format_date(extras, date));
+    let remaining = format_remaining(project, extras, task_index);
+
+    let r = &mut state.repr[task_index];
+    r.percent_complete = format!("{}%", progress.percent_complete);
+    r.percent_complete_err = None;
+    r.actual_start = format_actual(progress.actual_start);
+    r.actual_start_err = None;
+    r.actual_finish = format_actual(progress.actual_finish);
+    r.actual_finish_err = None;
+    r.remaining = remaining;
+    r.remaining_err = None;
+}
+
+/// Formats the duration a task still needs.
+fn format_remaining(project: &Project, extras: &ProjectExtras, task_index: usize) -> String {
+    let Some(duration) = scheduling::task_duration(project, extras, task_index) else {
+        return "".to_owned();
+    };
+    let percent_left = 100 - extras.progress(task_index).percent_complete as i64;
+    extras.duration_format().format(TimeDelta::minutes(
+        duration.num_minutes() * percent_left / 100,
+    ))
+}
+
+fn update_repr(state: &mut TasksState, project: &Project, extras: &ProjectExtras) {
     state.repr.clear();
 
     for (i, task) in project.tasks().enumerate() {
         state.repr.push(Repr {
+            id: extras.task_ids().id(i),
             name: task.name().to_owned(),
             description: task.description().to_owned(),
-            completed: task.completed(),
-            start: if let Some(start) = task.start() {
-                start.to_string()
-            } else {
-                "".to_owned()
-            },
-            is_start_err: false,
-            finish: if let Some(finish) = task.finish() {
-                finish.to_string()
-            } else {
-                "".to_owned()
-            },
-            is_finish_err: false,
-            duration: if let Some(duration) = task.duration() {
-                duration.to_string()
-            } else {
-                "".to_owned()
-            },
-            is_duration_err: false,
-            predecessors: project
-                .predecessors_indices(i)
-                .map(|p| p.to_string())
-                .collect::<Vec<String>>()
-                .join(";"),
-            is_predecessors_err: false,
-            successors: project
-                .successors_indices(i)
-                .map(|p| p.to_string())
-                .collect::<Vec<String>>()
-                .join(";"),
-            is_successors_err: false,
-            resources: "".to_owned(),
+            predecessors: format_predecessors(project, extras, i),
+            successors: format_successors(project, extras, i),
+            resources: format_assignments(extras, i),
+            resources_err: None,
+            work: format_work(extras, i),
+            work_err: None,
+            effort_driven: extras.is_effort_driven(i),
+            is_milestone: extras.is_milestone(i),
+            ..Default::default()
         });
+        update_start_finish_duration(state, project, extras, i);
+        update_progress_repr(state, project, extras, i);
+        update_constraint_repr(state, extras, i);
     }
+    update_outline(state, project, extras);
+    update_float(state, project, extras);
 }
 
-fn update_predecessors_repr(state: &mut TasksState, project: &mut Project, predecessors: &[usize]) {
+fn update_predecessors_repr(
+    state: &mut TasksState,
+    project: &Project,
+    extras: &ProjectExtras,
+    predecessors: &[usize],
+) {
     for &predecessor in predecessors {
-        state.repr[predecessor].successors = project
-            .successors_indices(predecessor)
-            .map(|p| p.to_string())
-            .collect::<Vec<String>>()
-            .join(";");
+        state.repr[predecessor].successors = format_successors(project, extras, predecessor);
     }
 }
 
-fn update_successors_repr(state: &mut TasksState, project: &mut Project, successors: &[usize]) {
+fn update_successors_repr(
+    state: &mut TasksState,
+    project: &Project,
+    extras: &ProjectExtras,
+    successors: &[usize],
+) {
     for &successor in successors {
-        state.repr[successor].predecessors = project
-            .predecessors_indices(successor)
-            .map(|p| p.to_string())
-            .collect::<Vec<String>>()
-            .join(";");
+        state.repr[successor].predecessors = format_predecessors(project, extras, successor);
     }
 }
 
-fn parse_indices(s: &str) -> Option<Vec<usize>> {
-    let bytes = s.as_bytes();
+fn update_constraint_repr(state: &mut TasksState, extras: &ProjectExtras, task_index: usize) {
+    let constraint = extras.constraint(task_index);
+    let deadline = extras.deadline(task_index);
+
+    let r = &mut state.repr[task_index];
+    r.constraint_kind = constraint.map(|c| c.kind).unwrap_or_default();
+    r.constraint_date = constraint.map_or("".to_owned(), |c| format_date(extras, c.date));
+    r.constraint_date_err = None;
+    r.deadline = deadline.map_or("".to_owned(), |d| format_date(extras, d));
+    r.deadline_err = None;
+}
+
+fn update_work_repr(state: &mut TasksState, extras: &ProjectExtras, task_index: usize) {
+    let r = &mut state.repr[task_index];
+    r.resources = format_assignments(extras, task_index);
+    r.resources_err = None;
+    r.work = format_work(extras, task_index);
+    r.work_err = None;
+}
+
+/// Formats assignments the way [`parse_assignments`] reads them, leaving out
+/// full time units and missing work.
+fn format_assignments(extras: &ProjectExtras, task_index: usize) -> String {
+    extras
+        .assignments(task_index)
+        .map(|(res_id, assignment)| {
+            let mut s = extras.resource_ids().id(res_id).to_string();
+            if assignment.units != FULL_TIME_UNITS {
+                s.push_str(&format!("@{}%", assignment.units));
+            }
+            if assignment.work > TimeDelta::zero() {
+                s.push_str(&format!(
+                    "={}",
+                    extras.duration_format().format(assignment.work)
+                ));
+            }
+            s
+        })
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+fn format_work(extras: &ProjectExtras, task_index: usize) -> String {
+    if extras.assignments(task_index).next().is_none() {
+        return "".to_owned();
+    }
+    extras.duration_format().format(extras.work(task_index))
+}
+
+fn format_predecessors(project: &Project, extras: &ProjectExtras, task_index: usize) -> String {
+    format_links(
+        project
+            .predecessors_indices(task_index)
+            .map(|p| (extras.task_ids().id(p), extras.link(p, task_index))),
+        extras.duration_format(),
+    )
+}
+
+fn format_successors(project: &Project, extras: &ProjectExtras, task_index: usize) -> String {
+    format_links(
+        project
+            .successors_indices(task_index)
+            .map(|s| (extras.task_ids().id(s), extras.link(task_index, s))),
+        extras.duration_format(),
+    )
+}
+
+/// Reads typed links into task indices, given the predecessor and the
+/// successor of the link with each task at the other end. Explains why the
+/// links are rejected if they don't parse, refer to missing tasks, or would
+/// make a task depend on itself.
+fn resolve_links(
+    project: &Project,
+    extras: &ProjectExtras,
+    s: &str,
+    ends: impl Fn(usize) -> (usize, usize),
+) -> Result<Vec<(usize, Link)>, String> {
+    let duration_format = extras.duration_format();
+    let links = parse_links(s, duration_format).ok_or_else(|| {
+        match s
+            .split(';')
+            .find(|link_s| parse_links(link_s, duration_format).is_none())
+        {
+            Some(link_s) => format!("\"{}\" isn't a link, such as 2 or 2SS+1d", link_s.trim()),
+            None => "Links are separated by semicolons, such as 1;2SS+1d".to_owned(),
+        }
+    })?;
+
+    let ids = extras.task_ids();
+    links
+        .into_iter()
+        .map(|(id, link)| {
+            let task = ids
+                .index(id, project.tasks().count())
+                .ok_or_else(|| format!("There's no task {id}"))?;
+            let (predecessor, successor) = ends(task);
+            if predecessor == successor {
+                return Err(format!("Task {id} can't be linked to itself"));
+            }
+            if let Some(path) = link_path(project, successor, predecessor) {
+                let cycle = std::iter::once(predecessor)
+                    .chain(path)
+                    .map(|t| ids.id(t).to_string())
+                    .collect::<Vec<String>>();
+                return Err(format!(
+                    "Tasks would depend on themselves: {}",
+                    cycle.join(" → ")
+                ));
+            }
+            Ok((task, link))
+        })
+        .collect()
+}
+
+/// Tasks on the shortest way from `from` to `to` along links, both included,
+/// if `to` comes after `from`.
+fn link_path(project: &Project, from: usize, to: usize) -> Option<Vec<usize>> {
+    let mut previous = HashMap::from([(from, from)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(task) = queue.pop_front() {
+        if task == to {
+            let mut path = vec![to];
+            while path[path.len() - 1] != from {
+                path.push(previous[&path[path.len() - 1]]);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for successor in project.successors_indices(task) {
+            if let Entry::Vacant(entry) = previous.entry(successor) {
+                entry.insert(task);
+                queue.push_back(successor);
+            }
+        }
+    }
+    None
+}
+
+fn resource_index(project: &Project, extras: &ProjectExtras, id: usize) -> Option<usize> {
+    extras.resource_ids().index(id, project.resources().len())
+}
+
+/// Fields that can be imported from CSV, in the order of the mapped rows.
+pub const CSV_FIELDS: [&str; 9] = [
+    "Name",
+    "Description",
+    "% Complete",
+    "Start",
+    "Finish",
+    "Duration",
+    "Predecessors",
+    "WBS",
+    "Task ID",
+];
+
+pub fn csv_table(state: &TasksState) -> CsvTable {
+    let headers = [
+        "Task ID",
+        "WBS",
+        "Name",
+        "Description",
+        "% Complete",
+        "Actual Start",
+        "Actual Finish",
+        "Remaining",
+        "Start",
+        "Finish",
+        "Duration",
+        "Predecessors",
+        "Successors",
+        "Resources",
+        "Work",
+        "Constraint",
+        "Constraint Date",
+        "Deadline",
+    ];
+    let rows = state
+        .repr
+        .iter()
+        .map(|r| {
+            vec![
+                r.id.to_string(),
+                r.wbs.clone(),
+                r.name.clone(),
+                r.description.clone(),
+                r.percent_complete.clone(),
+                r.actual_start.clone(),
+                r.actual_finish.clone(),
+                r.remaining.clone(),
+                r.start.clone(),
+                r.finish.clone(),
+                r.duration.clone(),
+                r.predecessors.clone(),
+                r.successors.clone(),
+                r.resources.clone(),
+                r.work.clone(),
+                r.constraint_kind.to_string(),
+                r.constraint_date.clone(),
+                r.deadline.clone(),
+            ]
+        })
+        .collect();
+
+    CsvTable {
+        headers: headers.map(ToOwned::to_owned).to_vec(),
+        rows,
+    }
+}
+
+/// Appends the rows to the project, feeding every value through [`update`]
+/// so that it's validated exactly as if it was typed in the grid.
+///
+/// Predecessors refer to the Task IDs of the rows in the file, or to their
+/// position from 0 if the file has no Task IDs, and tasks are nested under
+/// the rows whose WBS number is the prefix of theirs. Imported tasks get new
+/// IDs. Returns a description of every row with rejected values or links to
+/// tasks missing from the file.
+pub fn import_csv(
+    state: &mut TasksState,
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    rows: &[Vec<Option<String>>],
+) -> Vec<String> {
+    let offset = state.repr.len();
+
+    for row in rows {
+        let field = |f: usize| row[f].clone().filter(|v| !v.is_empty());
+
+        update(
+            state,
+            project,
+            extras,
+            TasksMessage::UpdateNewTask(field(0).unwrap_or_default()),
+        );
+        update(state, project, extras, TasksMessage::CreateNewTask);
+        let i = state.repr.len() - 1;
+
+        if let Some(description) = field(1) {
+            update(
+                state,
+                project,
+                extras,
+                TasksMessage::UpdateDescription(i, description),
+            );
+        }
+        if let Some(start) = field(3) {
+            update(state, project, extras, TasksMessage::UpdateStart(i, start));
+        }
+        if let Some(finish) = field(4) {
+            update(
+                state,
+                project,
+                extras,
+                TasksMessage::UpdateFinish(i, finish),
+            );
+        }
+        if let Some(duration) = field(5) {
+            update(
+                state,
+                project,
+                extras,
+                TasksMessage::UpdateDuration(i, duration),
+            );
+        }
+        // Older files only tell whether tasks are completed. Progress goes
+        // after the dates, since it fills in the actual ones.
+        if let Some(percent_complete) = field(2) {
+            let percent_complete = match percent_complete.to_lowercase().as_str() {
+                "true" | "yes" | "x" => "100".to_owned(),
+                "false" | "no" => "0".to_owned(),
+                _ => percent_complete,
+            };
+            update(
+                state,
+                project,
+                extras,
+                TasksMessage::UpdatePercentComplete(i, percent_complete),
+            );
+        }
+    }
+
+    // Nesting and predecessors go last, since they may refer to rows further
+    // down the file.
+    let mut warnings = Vec::new();
+    let mut numbers = HashMap::new();
+    for (row_index, row) in rows.iter().enumerate() {
+        let Some(number) = row[7].as_deref().map(str::trim).filter(|n| !n.is_empty()) else {
+            continue;
+        };
+        match numbers.entry(number) {
+            Entry::Occupied(_) => warnings.push(format!(
+                "Tasks row {}: WBS {number} is used by an earlier row",
+                row_index + 1
+            )),
+            Entry::Vacant(entry) => {
+                entry.insert(offset + row_index);
+            }
+        }
+    }
+    for (number, &task) in &numbers {
+        if let Some((parent_number, _)) = number.rsplit_once('.')
+            && let Some(&parent) = numbers.get(parent_number)
+        {
+            let _ = extras.set_parent(task, Some(parent));
+        }
+    }
+    update_outline(state, project, extras);
+    reschedule(state, project, extras);
+
+    let mut tasks_by_id = HashMap::new();
+    for (row_index, row) in rows.iter().enumerate() {
+        let Some(id) = row[8].as_deref().map(str::trim).filter(|id| !id.is_empty()) else {
+            continue;
+        };
+        match id.parse::<usize>() {
+            Ok(id) => {
+                if tasks_by_id.insert(id, offset + row_index).is_some() {
+                    warnings.push(format!(
+                        "Tasks row {}: Task ID {id} is used by an earlier row",
+                        row_index + 1
+                    ));
+                }
+            }
+            Err(_) => warnings.push(format!(
+                "Tasks row {}: \"{id}\" isn't a Task ID",
+                row_index + 1
+            )),
+        }
+    }
+    let task_of = |id: usize| {
+        if tasks_by_id.is_empty() {
+            (id < rows.len()).then_some(offset + id)
+        } else {
+            tasks_by_id.get(&id).copied()
+        }
+    };
+
+    for (row_index, row) in rows.iter().enumerate() {
+        if let Some(predecessors) = row[6].clone().filter(|v| !v.is_empty()) {
+            let predecessors = match parse_links(&predecessors, extras.duration_format()) {
+                Some(links) => {
+                    let links = links
+                        .into_iter()
+                        .filter_map(|(id, link)| match task_of(id) {
+                            Some(task) => Some((extras.task_ids().id(task), link)),
+                            None => {
+                                warnings.push(format!(
+                                    "Tasks row {}: there's no task {id} in the file",
+                                    row_index + 1
+                                ));
+                                None
+                            }
+                        })
+                        .collect::<Vec<(usize, Link)>>();
+                    format_links(links.into_iter(), extras.duration_format())
+                }
+                None => predecessors,
+            };
+            update(
+                state,
+                project,
+                extras,
+                TasksMessage::UpdatePredecessors(offset + row_index, predecessors),
+            );
+        }
+    }
+
+    warnings.extend(
+        state.repr[offset..]
+            .iter()
+            .enumerate()
+            .filter_map(|(row_index, r)| {
+                let rejected = [
+                    ("Start", r.start_err.is_some()),
+                    ("Finish", r.finish_err.is_some()),
+                    ("Duration", r.duration_err.is_some()),
+                    ("% Complete", r.percent_complete_err.is_some()),
+                    ("Predecessors", r.predecessors_err.is_some()),
+                ]
+                .into_iter()
+                .filter_map(|(field, is_err)| is_err.then_some(field))
+                .collect::<Vec<&str>>();
+
+                (!rejected.is_empty()).then(|| {
+                    format!(
+                        "Tasks row {}: rejected {}",
+                        row_index + 1,
+                        rejected.join(", ")
+                    )
+                })
+            }),
+    );
+    warnings
+}
+
+/// Parses a list of links such as `3;5SS-4h;7FF+2d`: the ID of the task at
+/// the other end of each link, optionally followed by the type of the link
+/// (finish-to-start by default) and a lag, or a lead if negative. Lags are
+/// working time in `duration_format`, like durations.
+fn parse_links(s: &str, duration_format: &DurationFormat) -> Option<Vec<(usize, Link)>> {
     static RE: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r"^[0-9]{1,3}(;[0-9]{1,3})*$")
+        Regex::new(r"^(?i)([0-9]{1,6})(FS|SS|FF|SF)?(?:([+-])(.+))?$")
             .expect("It wasn't possible to compile a hardcoded regex. This is a bug.")
     });
-    if RE.is_match(bytes) {
-        Some(
-            s.split(';')
-                .map(|index_s| {
-                    index_s.parse::<usize>().unwrap_or_else(|_| {
-                        panic!(
-                        "It should have been possible to parse {index_s} as usize. This is a bug."
-                    )
-                    })
-                })
-                .collect(),
-        )
+
+    if s.trim().is_empty() {
+        return Some(Vec::new());
+    }
+
+    s.split(';')
+        .map(|link_s| {
+            let captures = RE.captures(link_s.trim().as_bytes())?;
+            let text = |i: usize| {
+                captures
+                    .get(i)
+                    .map(|m| String::from_utf8_lossy(m.as_bytes()).to_uppercase())
+            };
+
+            let index = text(1)?.parse::<usize>().ok()?;
+            let kind = match text(2).as_deref() {
+                None | Some("FS") => LinkKind::FinishToStart,
+                Some("SS") => LinkKind::StartToStart,
+                Some("FF") => LinkKind::FinishToFinish,
+                Some(_) => LinkKind::StartToFinish,
+            };
+            let lag = match text(4) {
+                Some(lag) => duration_format.parse(&lag)?,
+                None => TimeDelta::zero(),
+            };
+            let lag = if text(3).as_deref() == Some("-") {
+                -lag
+            } else {
+                lag
+            };
+
+            Some((index, Link { kind, lag }))
+        })
+        .collect()
+}
+
+/// Parses a list of assignments such as `0;3@50%=20h`: the ID of a resource,
+/// as shown in the Personnel and Materials panes, optionally followed by its
+/// units (full time by default) and its work, in `duration_format`. IDs are
+/// turned into indices by `resource_index`. Explains why the assignments are
+/// rejected if they don't parse or refer to missing resources.
+fn parse_assignments(
+    s: &str,
+    resource_index: impl Fn(usize) -> Option<usize>,
+    duration_format: &DurationFormat,
+) -> Result<Vec<(usize, u16, Option<TimeDelta>)>, String> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^([0-9]{1,6})(?:@([0-9]{1,4})%?)?(?:=(.+))?$")
+            .expect("It wasn't possible to compile a hardcoded regex. This is a bug.")
+    });
+
+    if s.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    s.split(';')
+        .map(|assignment_s| {
+            let assignment_s = assignment_s.trim();
+            let captures = RE.captures(assignment_s.as_bytes()).ok_or_else(|| {
+                format!("\"{assignment_s}\" isn't an assignment, such as 3 or 3@50%=20h")
+            })?;
+            let text = |i: usize| {
+                captures
+                    .get(i)
+                    .map(|m| String::from_utf8_lossy(m.as_bytes()).into_owned())
+            };
+
+            let id = text(1)
+                .and_then(|id| id.parse::<usize>().ok())
+                .expect("The regex only matches numbers. This is a bug.");
+            let res_id = resource_index(id).ok_or_else(|| format!("There's no resource {id}"))?;
+            let units = text(2)
+                .map_or(Some(FULL_TIME_UNITS), |u| u.parse::<u16>().ok())
+                .filter(|&units| units > 0)
+                .ok_or_else(|| format!("Resource {id} must work more than 0%"))?;
+            let work = match text(3) {
+                Some(work) => Some(duration_format.parse(&work).ok_or_else(|| {
+                    format!("\"{work}\" isn't an amount of work, such as 20h or 2.5d")
+                })?),
+                None => None,
+            };
+
+            Ok((res_id, units, work))
+        })
+        .collect()
+}
+
+/// Parses a percentage such as `60%` or `60`, up to 100.
+fn parse_percent(s: &str) -> Option<u8> {
+    s.trim()
+        .trim_end_matches('%')
+        .trim_end()
+        .parse::<u8>()
+        .ok()
+        .filter(|&percent| percent <= 100)
+}
+
+/// Formats links the way [`parse_links`] reads them, leaving out the type and
+/// lag of plain finish-to-start links.
+pub fn format_links(
+    links: impl Iterator<Item = (usize, Link)>,
+    duration_format: &DurationFormat,
+) -> String {
+    links
+        .map(|(id, link)| {
+            if link == Link::default() {
+                return id.to_string();
+            }
+
+            let lag = if link.lag == TimeDelta::zero() {
+                "".to_owned()
+            } else {
+                let sign = if link.lag < TimeDelta::zero() {
+                    '-'
+                } else {
+                    '+'
+                };
+                format!("{sign}{}", duration_format.format(link.lag.abs()))
+            };
+            format!("{id}{}{lag}", link.kind)
+        })
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+/// A cell of the row `r`, highlighted if the task is on the critical path.
+fn row_cell<'a>(
+    r: &Repr,
+    placeholder: impl std::fmt::Display,
+    value: impl std::fmt::Display,
+    error: Option<&'a str>,
+) -> DataCell<'a, TasksMessage> {
+    if r.is_critical {
+        critical_data_cell(placeholder, value, error)
     } else {
-        None
+        data_cell(placeholder, value, error)
+    }
+}
+
+/// A date cell, where a right click opens the date picker if `on_pick` is
+/// given.
+fn date_cell<'a>(
+    cell: DataCell<'a, TasksMessage>,
+    on_pick: Option<TasksMessage>,
+) -> Element<'a, TasksMessage> {
+    match on_pick {
+        Some(on_pick) => mouse_area(cell).on_right_press(on_pick).into(),
+        None => cell.into(),
     }
 }
 
 pub fn view(state: &TasksState) -> Element<'_, TasksMessage> {
     let headers = Row::new()
         .push(data_label("Task ID"))
+        .push(data_label("WBS"))
         .push(data_label("Name"))
         .push(data_label("Description"))
-        .push(data_label("Completed"))
         .push(data_label("Start"))
         .push(data_label("Finish"))
         .push(data_label("Duration"))
+        .push(data_label("Milestone"))
+        .push(data_label("% Complete"))
+        .push(data_label("Actual Start"))
+        .push(data_label("Actual Finish"))
+        .push(data_label("Remaining"))
         .push(data_label("Predecessors"))
         .push(data_label("Successors"))
-        .push(data_label("Resources"));
+        .push(data_label("Resources"))
+        .push(data_label("Work"))
+        .push(data_label("Effort Driven"))
+        .push(data_label("Constraint"))
+        .push(data_label("Constraint Date"))
+        .push(data_label("Deadline"))
+        .push(data_label("Total Float"))
+        .push(data_label("Free Float"));
 
     let content_rows: Vec<Element<'_, _>> = state
-        .repr
+        .outline
         .iter()
-        .enumerate()
+        .map(|&i| (i, &state.repr[i]))
         .map(|(i, r)| {
+            // Dates and progress of summary tasks are rolled up from their
+            // children, and can't be edited. Resources are assigned to their
+            // children too.
+            let is_editable = !r.is_summary;
+            // Milestones have no duration, so their finish is their start.
+            let has_duration = is_editable && !r.is_milestone;
+            let has_constraint_date =
+                is_editable && r.constraint_kind != ConstraintKind::AsSoonAsPossible;
+
+            // Tasks that miss their dates are flagged, and tell why on hover.
+            let index = button(text(r.id).width(Length::Fill).align_x(Horizontal::Center))
+                .style(if state.selected == Some(i) {
+                    button::primary
+                } else if r.missed_dates.is_some() {
+                    button::danger
+                } else {
+                    button::text
+                })
+                .on_press(TasksMessage::Select(i))
+                .width(constants::WIDTH);
+            let index: Element<'_, _> = match &r.missed_dates {
+                Some(missed_dates) => tooltip(
+                    index,
+                    container(text(missed_dates).size(14))
+                        .padding(5)
+                        .style(container::rounded_box),
+                    tooltip::Position::Right,
+                )
+                .into(),
+                None => index.into(),
+            };
+            let constraint_kind: Element<'_, _> = if is_editable {
+                pick_list(ConstraintKind::ALL, Some(r.constraint_kind), move |k| {
+                    TasksMessage::SelectConstraint(i, k)
+                })
+                .width(constants::WIDTH)
+                .into()
+            } else {
+                data_label("").into()
+            };
+
             Row::new()
                 // Index
-                .push(data_label(i))
+                .push(index)
+                // WBS
+                .push(data_label(&r.wbs))
                 // Name
                 .push(
-                    data_cell(format!("Task n{i}"), &r.name, false)
+                    row_cell(r, format!("Task n{}", r.id), &r.name, None)
+                        .padding(Padding {
+                            left: 5. + r.depth as f32 * INDENT_WIDTH,
+                            ..Padding::new(5.)
+                        })
                         .on_input(move |n| TasksMessage::UpdateName(i, n)),
                 )
                 // Description
                 .push(
-                    data_cell("This task...", &r.description, false)
+                    row_cell(r, "This task...", &r.description, None)
                         .on_input(move |n| TasksMessage::UpdateDescription(i, n)),
                 )
-                // Completed
+                // Start
+                .push(date_cell(
+                    row_cell(r, "1992-04-01 09:15", &r.start, r.start_err.as_deref())
+                        .on_input_maybe(
+                            is_editable.then_some(move |s| TasksMessage::UpdateStart(i, s)),
+                        ),
+                    is_editable.then_some(TasksMessage::OpenDatePicker(i, DateField::Start)),
+                ))
+                // Finish
+                .push(date_cell(
+                    row_cell(r, "1992-04-03 17:00", &r.finish, r.finish_err.as_deref())
+                        .on_input_maybe(
+                            has_duration.then_some(move |s| TasksMessage::UpdateFinish(i, s)),
+                        ),
+                    has_duration.then_some(TasksMessage::OpenDatePicker(i, DateField::Finish)),
+                ))
+                // Duration
                 .push(
-                    container(
-                        checkbox("", r.completed)
-                            .on_toggle(move |_| TasksMessage::ToggleCompleted(i)),
-                    )
+                    row_cell(r, "1w 2d", &r.duration, r.duration_err.as_deref()).on_input_maybe(
+                        has_duration.then_some(move |d| TasksMessage::UpdateDuration(i, d)),
+                    ),
+                )
+                // Milestone
+                .push(
+                    container(checkbox("", r.is_milestone).on_toggle_maybe(
+                        is_editable.then_some(move |_| TasksMessage::ToggleMilestone(i)),
+                    ))
                     .width(100)
                     .height(50)
                     .align_x(Horizontal::Center)
                     .align_y(Vertical::Center),
                 )
-                // Start
-                .push(
-                    data_cell("1992-04-01 09:15", &r.start, r.is_start_err)
-                        .on_input(move |s| TasksMessage::UpdateStart(i, s)),
-                )
-                // Finish
+                // % Complete
                 .push(
-                    data_cell("1993-27-05 10:20", &r.finish, r.is_finish_err)
-                        .on_input(move |s| TasksMessage::UpdateFinish(i, s)),
+                    row_cell(
+                        r,
+                        "60%",
+                        &r.percent_complete,
+                        r.percent_complete_err.as_deref(),
+                    )
+                    .on_input_maybe(
+                        is_editable.then_some(move |p| TasksMessage::UpdatePercentComplete(i, p)),
+                    ),
                 )
-                // Duration
+                // Actual Start
+                .push(date_cell(
+                    row_cell(
+                        r,
+                        "1992-04-01 09:15",
+                        &r.actual_start,
+                        r.actual_start_err.as_deref(),
+                    )
+                    .on_input_maybe(
+                        is_editable.then_some(move |s| TasksMessage::UpdateActualStart(i, s)),
+                    ),
+                    is_editable.then_some(TasksMessage::OpenDatePicker(i, DateField::ActualStart)),
+                ))
+                // Actual Finish
+                .push(date_cell(
+                    row_cell(
+                        r,
+                        "1992-04-03 17:00",
+                        &r.actual_finish,
+                        r.actual_finish_err.as_deref(),
+                    )
+                    .on_input_maybe(
+                        is_editable.then_some(move |f| TasksMessage::UpdateActualFinish(i, f)),
+                    ),
+                    is_editable.then_some(TasksMessage::OpenDatePicker(i, DateField::ActualFinish)),
+                ))
+                // Remaining
                 .push(
-                    data_cell("48 h", &r.duration, r.is_duration_err)
-                        .on_input(move |d| TasksMessage::UpdateDuration(i, d)),
+                    row_cell(r, "16h", &r.remaining, r.remaining_err.as_deref()).on_input_maybe(
+                        has_duration.then_some(move |r| TasksMessage::UpdateRemaining(i, r)),
+                    ),
                 )
                 // Predecessors
                 .push(
-                    data_cell("1;2", &r.predecessors, r.is_predecessors_err)
-                        .on_input(move |p| TasksMessage::UpdatePredecessors(i, p)),
+                    row_cell(
+                        r,
+                        "1;2SS+1d",
+                        &r.predecessors,
+                        r.predecessors_err.as_deref(),
+                    )
+                    .on_input(move |p| TasksMessage::UpdatePredecessors(i, p)),
                 )
                 // Successors
                 .push(
-                    data_cell("1;2", &r.successors, r.is_successors_err)
+                    row_cell(r, "1;2SS+1d", &r.successors, r.successors_err.as_deref())
                         .on_input(move |p| TasksMessage::UpdateSuccessors(i, p)),
                 )
                 // Resources
                 .push(
-                    data_cell("", &r.resources, false)
-                        .on_input(move |res| TasksMessage::UpdateResources(i, res)),
+                    row_cell(r, "0;3@50%", &r.resources, r.resources_err.as_deref())
+                        .on_input_maybe(
+                            is_editable.then_some(move |a| TasksMessage::UpdateResources(i, a)),
+                        ),
                 )
+                // Work
+                .push(
+                    row_cell(r, "40h", &r.work, r.work_err.as_deref()).on_input_maybe(
+                        has_duration.then_some(move |w| TasksMessage::UpdateWork(i, w)),
+                    ),
+                )
+                // Effort Driven
+                .push(
+                    container(checkbox("", r.effort_driven).on_toggle_maybe(
+                        is_editable.then_some(move |_| TasksMessage::ToggleEffortDriven(i)),
+                    ))
+                    .width(100)
+                    .height(50)
+                    .align_x(Horizontal::Center)
+                    .align_y(Vertical::Center),
+                )
+                // Constraint
+                .push(constraint_kind)
+                // Constraint Date
+                .push(date_cell(
+                    row_cell(
+                        r,
+                        "1992-04-01 09:15",
+                        &r.constraint_date,
+                        r.constraint_date_err.as_deref(),
+                    )
+                    .on_input_maybe(
+                        has_constraint_date
+                            .then_some(move |d| TasksMessage::UpdateConstraintDate(i, d)),
+                    ),
+                    has_constraint_date.then_some(TasksMessage::OpenDatePicker(
+                        i,
+                        DateField::Constraint(r.constraint_kind),
+                    )),
+                ))
+                // Deadline
+                .push(date_cell(
+                    row_cell(
+                        r,
+                        "1992-04-03 17:00",
+                        &r.deadline,
+                        r.deadline_err.as_deref(),
+                    )
+                    .on_input_maybe(
+                        is_editable.then_some(move |d| TasksMessage::UpdateDeadline(i, d)),
+                    ),
+                    is_editable.then_some(TasksMessage::OpenDatePicker(i, DateField::Deadline)),
+                ))
+                // Total Float
+                .push(data_label(&r.total_float))
+                // Free Float
+                .push(data_label(&r.free_float))
+                // Outline
+                .push(
+                    button("Outdent")
+                        .on_press_maybe((r.depth > 0).then_some(TasksMessage::Outdent(i)))
+                        .width(80),
+                )
+                .push(button("Indent").on_press(TasksMessage::Indent(i)).width(80))
                 // Delete
                 .push(
                     button("Del")
@@ -375,15 +2234,23 @@ pub fn view(state: &TasksState) -> Element<'_, TasksMessage> {
     let new_row = Row::new()
         // Index
         .push(data_label(""))
+        // WBS
+        .push(data_label(""))
         // Name
         .push(
-            data_cell("New task name", &state.new_task, false)
+            data_cell("New task name", &state.new_task, None)
                 .on_input(TasksMessage::UpdateNewTask)
                 .on_submit(TasksMessage::CreateNewTask),
         )
         // Description
-        .push(data_cell("", "", false))
-        // Completed
+        .push(data_cell("", "", None))
+        // Start
+        .push(data_cell("", "", None))
+        // Finish
+        .push(data_cell("", "", None))
+        // Duration
+        .push(data_cell("", "", None))
+        // Milestone
         .push(
             container(checkbox("", false))
                 .height(30)
@@ -391,43 +2258,355 @@ pub fn view(state: &TasksState) -> Element<'_, TasksMessage> {
                 .align_x(Horizontal::Center)
                 .align_y(Vertical::Center),
         )
-        // Start
-        .push(data_cell("", "", false))
-        // Finish
-        .push(data_cell("", "", false))
-        // Duration
-        .push(data_cell("", "", false))
+        // % Complete
+        .push(data_cell("", "", None))
+        // Actual Start
+        .push(data_cell("", "", None))
+        // Actual Finish
+        .push(data_cell("", "", None))
+        // Remaining
+        .push(data_cell("", "", None))
         // Predecessors
-        .push(data_cell("", "", false))
+        .push(data_cell("", "", None))
         // Successors
-        .push(data_cell("", "", false))
+        .push(data_cell("", "", None))
         // Resources
-        .push(data_cell("", "", false));
+        .push(data_cell("", "", None))
+        // Work
+        .push(data_cell("", "", None))
+        // Effort Driven
+        .push(
+            container(checkbox("", false))
+                .height(30)
+                .width(100)
+                .align_x(Horizontal::Center)
+                .align_y(Vertical::Center),
+        )
+        // Constraint
+        .push(data_label(""))
+        // Constraint Date
+        .push(data_cell("", "", None))
+        // Deadline
+        .push(data_cell("", "", None))
+        // Total Float
+        .push(data_label(""))
+        // Free Float
+        .push(data_label(""));
+
+    let date_picker = state.date_picker.map(|picker| {
+        let field = match picker.field {
+            DateField::Start => "Start",
+            DateField::Finish => "Finish",
+            DateField::ActualStart => "Actual Start",
+            DateField::ActualFinish => "Actual Finish",
+            DateField::Constraint(_) => "Constraint Date",
+            DateField::Deadline => "Deadline",
+        };
+        let selected = state.repr.get(picker.task).and_then(|r| {
+            let date = match picker.field {
+                DateField::Start => &r.start,
+                DateField::Finish => &r.finish,
+                DateField::ActualStart => &r.actual_start,
+                DateField::ActualFinish => &r.actual_finish,
+                DateField::Constraint(_) => &r.constraint_date,
+                DateField::Deadline => &r.deadline,
+            };
+            NaiveDateTime::parse_from_str(date, DATE_FORMAT)
+                .ok()
+                .map(|date| date.date())
+        });
+
+        Column::new()
+            .push(
+                row![
+                    text(format!(
+                        "{field} of task {}",
+                        state.repr.get(picker.task).map_or(0, |r| r.id)
+                    ))
+                    .size(14),
+                    button("Close").on_press(TasksMessage::CloseDatePicker),
+                ]
+                .spacing(10),
+            )
+            .push(date_picker(
+                picker.month,
+                selected,
+                TasksMessage::PickDate,
+                TasksMessage::ShowMonth,
+            ))
+            .spacing(5)
+            .padding(5)
+    });
 
     Column::new()
+        .push(
+            text(format!(
+                "Project: {}% complete. Right-click a date to pick it from a calendar.",
+                state.project_progress
+            ))
+            .size(14),
+        )
         .push(headers)
         .extend(content_rows)
         .push(new_row)
+        .push(date_picker)
         .height(Length::Shrink)
         .into()
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeDelta;
+    use planter_core::{project::Project, task::Task};
     use proptest::{prelude::Strategy, proptest};
 
-    use crate::ui::tasks_page::parse_indices;
+    use crate::{
+        duration_format::{DurationFormat, DurationUnit},
+        extras::{Ids, ProjectExtras},
+        scheduling::{Link, LinkKind, task_duration},
+        ui::{
+            csv_import::{CsvGrid, CsvImportState},
+            tasks_page::{
+                TasksMessage, TasksState, csv_table, format_links, import_csv, parse_assignments,
+                parse_links, resolve_links, update,
+            },
+        },
+    };
 
     fn string_array_strategy() -> impl Strategy<Value = String> {
-        r"[0-9]{1,3}(;[0-9]{1,3})*".prop_map(|s| s)
+        r"[0-9]{1,3}((FS|SS|FF|SF)([+-][0-9]{1,3}[dhm])?)?(;[0-9]{1,3}((FS|SS|FF|SF)([+-][0-9]{1,3}[dhm])?)?)*"
+            .prop_map(|s| s)
     }
 
     proptest! {
         #[test]
-        fn parse_indices_works(s in string_array_strategy()) {
-            let arr = parse_indices(&s);
+        fn parse_links_works(s in string_array_strategy()) {
+            let links = parse_links(&s, &DurationFormat::default());
+
+            assert!(links.is_some());
+        }
+    }
+
+    #[test]
+    fn links_round_trip() {
+        let mut format = DurationFormat::default();
+        format.unit = DurationUnit::Days;
+        let links = parse_links("3;5ss-4h; 7FF+2d;8SF", &format).unwrap();
+
+        assert_eq!(
+            links[1],
+            (
+                5,
+                Link {
+                    kind: LinkKind::StartToStart,
+                    lag: TimeDelta::hours(-4),
+                }
+            )
+        );
+        assert_eq!(links[2].1.lag, TimeDelta::hours(16));
+        assert_eq!(
+            format_links(links.into_iter(), &format),
+            "3;5SS-4h;7FF+2d;8SF"
+        );
+    }
+
+    #[test]
+    fn link_lags_are_working_time() {
+        // Days are 8 hours of working time by default.
+        let format = DurationFormat::default();
+        let links = parse_links("1FS+1d", &format).unwrap();
+
+        assert_eq!(links[0].1.lag, TimeDelta::hours(8));
+        assert_eq!(format_links(links.into_iter(), &format), "1FS+8h");
+    }
+
+    #[test]
+    fn milestones_give_back_their_duration() {
+        let mut state = TasksState::default();
+        let mut project = Project::new("Bridge");
+        let mut extras = ProjectExtras::default();
+        for message in [
+            TasksMessage::UpdateNewTask("Inspection".to_owned()),
+            TasksMessage::CreateNewTask,
+            TasksMessage::UpdateStart(0, "2025-01-06 08:00".to_owned()),
+            TasksMessage::UpdateDuration(0, "2d".to_owned()),
+        ] {
+            update(&mut state, &mut project, &mut extras, message);
+        }
+        let finish = project.task(0).unwrap().finish();
+        let repr = (state.repr[0].finish.clone(), state.repr[0].duration.clone());
+
+        update(
+            &mut state,
+            &mut project,
+            &mut extras,
+            TasksMessage::ToggleMilestone(0),
+        );
+        assert_eq!(project.task(0).unwrap().finish(), None);
+        assert_eq!(state.repr[0].finish, state.repr[0].start);
+
+        update(
+            &mut state,
+            &mut project,
+            &mut extras,
+            TasksMessage::ToggleMilestone(0),
+        );
+        assert!(finish.is_some());
+        assert_eq!(project.task(0).unwrap().finish(), finish);
+        assert_eq!(
+            (state.repr[0].finish.clone(), state.repr[0].duration.clone()),
+            repr
+        );
+        assert_eq!(extras.milestone_duration(0), None);
+    }
+
+    #[test]
+    fn unscheduled_tasks_keep_minutes() {
+        let mut state = TasksState::default();
+        let mut project = Project::new("Bridge");
+        let mut extras = ProjectExtras::default();
+        for message in [
+            TasksMessage::UpdateNewTask("Inspection".to_owned()),
+            TasksMessage::CreateNewTask,
+            TasksMessage::UpdateDuration(0, "90m".to_owned()),
+        ] {
+            update(&mut state, &mut project, &mut extras, message);
+        }
+        let duration = TimeDelta::minutes(90);
+
+        assert_eq!(state.repr[0].duration_err, None);
+        assert_eq!(
+            state.repr[0].duration,
+            extras.duration_format().format(duration)
+        );
+        assert_eq!(task_duration(&project, &extras, 0), Some(duration));
+
+        let message = TasksMessage::UpdateStart(0, "2025-01-06 08:00".to_owned());
+        update(&mut state, &mut project, &mut extras, message);
+        assert_eq!(task_duration(&project, &extras, 0), Some(duration));
+    }
 
-            assert!(arr.is_some());
+    #[test]
+    fn rejected_links_are_explained() {
+        let mut project = Project::new("Bridge");
+        for name in ["Foundations", "Piers", "Deck"] {
+            project.add_task(Task::new(name.to_owned()));
         }
+        project.update_predecessors(1, &[0]).unwrap();
+        project.update_predecessors(2, &[1]).unwrap();
+        let mut extras = ProjectExtras::default();
+        extras.set_task_ids(Ids::new(vec![3, 7, 12], 13).unwrap());
+        let predecessors_of = |task: usize, s: &str| {
+            resolve_links(&project, &extras, s, |predecessor| (predecessor, task))
+        };
+
+        assert_eq!(
+            predecessors_of(0, "12"),
+            Err("Tasks would depend on themselves: 12 → 3 → 7 → 12".to_owned())
+        );
+        assert_eq!(
+            predecessors_of(0, "3"),
+            Err("Task 3 can't be linked to itself".to_owned())
+        );
+        assert_eq!(predecessors_of(0, "4"), Err("There's no task 4".to_owned()));
+        assert_eq!(
+            predecessors_of(0, "7; 12x"),
+            Err("\"12x\" isn't a link, such as 2 or 2SS+1d".to_owned())
+        );
+        assert_eq!(predecessors_of(2, "3"), Ok(vec![(0, Link::default())]));
+        assert_eq!(predecessors_of(2, " "), Ok(vec![]));
+    }
+
+    #[test]
+    fn csv_links_survive_deleted_tasks() {
+        let mut state = TasksState::default();
+        let mut project = Project::new("Bridge");
+        let mut extras = ProjectExtras::default();
+        for name in ["Survey", "Foundations", "Piers", "Deck"] {
+            let message = TasksMessage::UpdateNewTask(name.to_owned());
+            update(&mut state, &mut project, &mut extras, message);
+            update(
+                &mut state,
+                &mut project,
+                &mut extras,
+                TasksMessage::CreateNewTask,
+            );
+        }
+        let message = TasksMessage::UpdatePredecessors(3, "1;2".to_owned());
+        update(&mut state, &mut project, &mut extras, message);
+        update(
+            &mut state,
+            &mut project,
+            &mut extras,
+            TasksMessage::DeleteTask(0),
+        );
+
+        let mut table = csv_table(&state);
+        let predecessors = table.headers.iter().position(|h| h == "Predecessors");
+        table.rows[1][predecessors.unwrap()] = "9".to_owned();
+        let rows = CsvImportState::new(CsvGrid::Tasks, table).mapped_rows();
+        let mut state = TasksState::default();
+        let mut project = Project::new("Bridge");
+        let mut extras = ProjectExtras::default();
+        let warnings = import_csv(&mut state, &mut project, &mut extras, &rows);
+
+        assert_eq!(project.predecessors_indices(2).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(project.predecessors_indices(1).count(), 0);
+        assert!(warnings.contains(&"Tasks row 2: there's no task 9 in the file".to_owned()));
+    }
+
+    #[test]
+    fn csv_wbs_numbers_are_trimmed_and_unique() {
+        let row = |name: &str, wbs: &str| {
+            let mut row = vec![None; 9];
+            row[0] = Some(name.to_owned());
+            row[7] = Some(wbs.to_owned());
+            row
+        };
+        let rows = [
+            row("Bridge", " 1 "),
+            row("Piers", "1.1"),
+            row("Deck", "1.1"),
+            row("Paint", " "),
+        ];
+        let mut state = TasksState::default();
+        let mut project = Project::new("Bridge");
+        let mut extras = ProjectExtras::default();
+        let warnings = import_csv(&mut state, &mut project, &mut extras, &rows);
+
+        assert_eq!(extras.parent(1), Some(0));
+        assert_eq!(extras.parent(2), None);
+        assert_eq!(extras.parent(3), None);
+        assert_eq!(
+            warnings,
+            ["Tasks row 3: WBS 1.1 is used by an earlier row".to_owned()]
+        );
+    }
+
+    #[test]
+    fn parse_assignments_works() {
+        let format = DurationFormat::default();
+        let index = |id: usize| [5, 7, 8, 12345].iter().position(|&i| i == id);
+
+        assert_eq!(
+            parse_assignments("5; 8@50%=7h30m;7@25=1d", index, &format),
+            Ok(vec![
+                (0, 100, None),
+                (2, 50, Some(TimeDelta::minutes(450))),
+                (1, 25, Some(TimeDelta::hours(8)))
+            ])
+        );
+        assert_eq!(
+            parse_assignments("12345@50", index, &format),
+            Ok(vec![(3, 50, None)])
+        );
+        assert_eq!(parse_assignments("", |_| None, &format), Ok(vec![]));
+        assert_eq!(
+            parse_assignments("3", index, &format),
+            Err("There's no resource 3".to_owned())
+        );
+        assert!(parse_assignments("5@0%", index, &format).is_err());
+        assert!(parse_assignments("5=1 fortnight", index, &format).is_err());
+        assert!(parse_assignments("5,7", index, &format).is_err());
     }
 }
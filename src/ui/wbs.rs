@@ -1,11 +0,0 @@
-// struct Wbs {}
-
-// enum Message {}
-
-// impl Wbs {
-//     fn update(&mut self, message: Message) {
-//         match message {}
-//     }
-
-//     // fn view(&self) -> Element<Message> {}
-// }
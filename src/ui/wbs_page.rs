@@ -0,0 +1,279 @@
+//! The work breakdown structure, as a tree of tasks.
+//!
+//! Edits made here are applied through [`tasks_page::update`], and the
+//! selected task is the one of the Tasks pane.
+
+use std::{collections::BTreeSet, fmt::Display};
+
+use iced::{
+    Alignment, Element, Length, Padding, Theme,
+    widget::{Column, Space, button, container, mouse_area, pick_list, row, text},
+};
+use planter_core::project::Project;
+
+use crate::extras::{OutlineEntry, ProjectExtras};
+
+use super::tasks_page::{self, TasksMessage, TasksState};
+
+/// Indentation of nested tasks, per level.
+const INDENT_WIDTH: f32 = 20.;
+const TOGGLE_WIDTH: f32 = 25.;
+
+#[derive(Debug, Default)]
+pub struct WbsState {
+    /// Summary tasks whose children are hidden.
+    collapsed: BTreeSet<usize>,
+    /// Level the tree was last folded to.
+    level: Option<Level>,
+    /// Task being dragged, from the press of the mouse button to its release.
+    dragged: Option<usize>,
+    /// Where the dragged task would be dropped.
+    hovered: Option<DropTarget>,
+}
+
+/// Where a dragged task can be dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropTarget {
+    /// Under another task.
+    Task(usize),
+    TopLevel,
+}
+
+/// Depth the tree is expanded to, or `None` for the whole tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level(Option<usize>);
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(level) => write!(f, "Level {level}"),
+            None => f.write_str("All levels"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WbsMessage {
+    ToggleCollapsed(usize),
+    ExpandToLevel(Level),
+    Pressed(usize),
+    Entered(DropTarget),
+    Released(DropTarget),
+    TaskDeleted(usize),
+}
+
+impl WbsMessage {
+    /// Whether the message may change the project, rather than only how the
+    /// tree is shown. Dropping a task may nest it.
+    pub fn is_edit(&self) -> bool {
+        matches!(self, WbsMessage::Released(_))
+    }
+}
+
+impl WbsState {
+    /// Keeps the tree folded as it was when the whole project is replaced by
+    /// another version of it, such as on undo, following tasks by their ID
+    /// from the `previous` extras to the `current` ones.
+    pub fn remap(&mut self, previous: &ProjectExtras, current: &ProjectExtras, task_count: usize) {
+        self.collapsed = self
+            .collapsed
+            .iter()
+            .filter_map(|&task| {
+                current
+                    .task_ids()
+                    .index(previous.task_ids().id(task), task_count)
+            })
+            .collect();
+        self.dragged = None;
+        self.hovered = None;
+    }
+}
+
+pub fn update(
+    state: &mut WbsState,
+    tasks_state: &mut TasksState,
+    project: &mut Project,
+    extras: &mut ProjectExtras,
+    message: WbsMessage,
+) {
+    match message {
+        WbsMessage::ToggleCollapsed(i) => {
+            if !state.collapsed.remove(&i) {
+                state.collapsed.insert(i);
+            }
+        }
+        WbsMessage::ExpandToLevel(level) => {
+            let task_count = project.tasks().count();
+            state.level = Some(level);
+            state.collapsed = extras
+                .outline(task_count)
+                .into_iter()
+                .filter(|entry| level.0.is_some_and(|level| entry.depth + 1 >= level))
+                .map(|entry| entry.task)
+                .filter(|&task| extras.is_summary(task, task_count))
+                .collect();
+        }
+        WbsMessage::Pressed(i) => {
+            state.dragged = Some(i);
+            state.hovered = None;
+        }
+        WbsMessage::Entered(target) => {
+            if state.dragged.is_some() {
+                state.hovered = Some(target);
+            }
+        }
+        WbsMessage::Released(target) => {
+            state.hovered = None;
+            let Some(dragged) = state.dragged.take() else {
+                return;
+            };
+            let message = match target {
+                // Pressing and releasing on the same task is a click.
+                DropTarget::Task(task) if task == dragged => TasksMessage::Select(task),
+                DropTarget::Task(parent) => {
+                    state.collapsed.remove(&parent);
+                    TasksMessage::SetParent(dragged, Some(parent))
+                }
+                DropTarget::TopLevel => TasksMessage::SetParent(dragged, None),
+            };
+            tasks_page::update(tasks_state, project, extras, message);
+        }
+        WbsMessage::TaskDeleted(i) => {
+            state.collapsed = state
+                .collapsed
+                .iter()
+                .filter(|&&task| task != i)
+                .map(|&task| if task > i { task - 1 } else { task })
+                .collect();
+            state.dragged = None;
+            state.hovered = None;
+        }
+    }
+}
+
+pub fn view<'a>(
+    state: &'a WbsState,
+    tasks_state: &'a TasksState,
+    project: &'a Project,
+    extras: &'a ProjectExtras,
+) -> Element<'a, WbsMessage> {
+    let task_count = project.tasks().count();
+    let outline = extras.outline(task_count);
+    let max_depth = outline
+        .iter()
+        .map(|entry| entry.depth + 1)
+        .max()
+        .unwrap_or(1);
+    let levels = (1..=max_depth)
+        .map(|level| Level(Some(level)))
+        .chain(std::iter::once(Level(None)))
+        .collect::<Vec<Level>>();
+
+    let rows = outline
+        .into_iter()
+        .filter(|entry| {
+            !extras
+                .ancestors(entry.task)
+                .any(|a| state.collapsed.contains(&a))
+        })
+        .map(|entry| {
+            let is_summary = extras.is_summary(entry.task, task_count);
+            view_row(state, tasks_state, project, entry, is_summary)
+        });
+
+    let mut tree = Column::new()
+        .push(
+            row![
+                text("Expand to"),
+                pick_list(levels, state.level, WbsMessage::ExpandToLevel),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center),
+        )
+        .push(text("Drag a task onto another one to nest it under it.").size(12))
+        .extend(rows)
+        .spacing(2)
+        .padding(5);
+
+    if state.dragged.is_some() {
+        let is_hovered = state.hovered == Some(DropTarget::TopLevel);
+        tree = tree.push(
+            mouse_area(
+                container(text("Drop here to move to the top level").size(12))
+                    .padding(5)
+                    .width(Length::Fill)
+                    .style(move |theme: &Theme| row_style(theme, false, is_hovered)),
+            )
+            .on_enter(WbsMessage::Entered(DropTarget::TopLevel))
+            .on_release(WbsMessage::Released(DropTarget::TopLevel)),
+        );
+    }
+
+    tree.into()
+}
+
+fn view_row<'a>(
+    state: &WbsState,
+    tasks_state: &TasksState,
+    project: &'a Project,
+    entry: OutlineEntry,
+    is_summary: bool,
+) -> Element<'a, WbsMessage> {
+    let task = entry.task;
+    let toggle: Element<'a, WbsMessage> = if is_summary {
+        button(
+            text(if state.collapsed.contains(&task) {
+                "+"
+            } else {
+                "-"
+            })
+            .size(12),
+        )
+        .style(button::text)
+        .padding(2)
+        .width(TOGGLE_WIDTH)
+        .on_press(WbsMessage::ToggleCollapsed(task))
+        .into()
+    } else {
+        Space::new(TOGGLE_WIDTH, 0).into()
+    };
+    let name = project
+        .task(task)
+        .expect("Outline entries should be tasks of the project. This is a bug.")
+        .name();
+
+    let is_selected = tasks_state.selected() == Some(task);
+    let is_hovered = state.hovered == Some(DropTarget::Task(task));
+
+    mouse_area(
+        container(
+            row![toggle, text(format!("{} {name}", entry.number))]
+                .spacing(5)
+                .align_y(Alignment::Center),
+        )
+        .padding(Padding {
+            left: entry.depth as f32 * INDENT_WIDTH,
+            ..Padding::new(2.)
+        })
+        .width(Length::Fill)
+        .style(move |theme: &Theme| row_style(theme, is_selected, is_hovered)),
+    )
+    .on_press(WbsMessage::Pressed(task))
+    .on_enter(WbsMessage::Entered(DropTarget::Task(task)))
+    .on_release(WbsMessage::Released(DropTarget::Task(task)))
+    .into()
+}
+
+fn row_style(theme: &Theme, is_selected: bool, is_hovered: bool) -> container::Style {
+    let palette = theme.extended_palette();
+
+    container::Style {
+        background: is_selected.then(|| palette.primary.weak.color.into()),
+        border: iced::Border {
+            color: palette.primary.strong.color,
+            width: if is_hovered { 1. } else { 0. },
+            radius: 2.0.into(),
+        },
+        ..Default::default()
+    }
+}